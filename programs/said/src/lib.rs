@@ -21,10 +21,52 @@ pub const TREASURY_AUTHORITY: Pubkey = pubkey!("H8nKbwHTTmnjgnsvqxRDpoEcTkU6uoqs
 
 pub const VERIFICATION_FEE: u64 = 10_000_000; // 0.01 SOL
 pub const VALIDATION_FEE: u64 = 1_000_000;    // 0.001 SOL
+pub const VERIFICATION_PERIOD_SECS: i64 = 90 * 24 * 60 * 60; // 90 days
+pub const VERIFICATION_GRACE_PERIOD_SECS: i64 = 7 * 24 * 60 * 60; // 7 days after expiry
+pub const REPUTATION_HALF_LIFE_SECS: i64 = 182 * 24 * 60 * 60; // ~6 months
+pub const FEEDBACK_BASE_WEIGHT: u32 = 100; // any wallet; SAID standing adds up to 6x more
+pub const FEEDBACK_MAX_AGE_DAYS: i64 = 365;
+pub const REPUTATION_DECAY_SCALE: u64 = 1_000_000; // decayed accumulators hold weight * this
+// Bayesian prior for confidence_score (adjustable in ProtocolConfig)
+pub const DEFAULT_REPUTATION_PRIOR_SCORE_BPS: u16 = 5_000;
+pub const DEFAULT_REPUTATION_PRIOR_WEIGHT: u32 = 500; // five unaffiliated reviews
+pub const MAX_PRIOR_WEIGHT: u32 = 10_000; // a hundred unaffiliated reviews
 
 pub const MIN_STAKE_LAMPORTS: u64 = 100_000_000; // 0.1 SOL
 pub const UNSTAKE_COOLDOWN_SECS: i64 = 7 * 24 * 60 * 60; // 7 days
 pub const EMERGENCY_UNSTAKE_PENALTY_BPS: u16 = 1000; // 10%
+// Default tier thresholds (adjustable in ProtocolConfig)
+pub const DEFAULT_SECURED_THRESHOLD: u64 = 100_000_000;         // 0.1 SOL
+pub const DEFAULT_PROFESSIONAL_THRESHOLD: u64 = 1_000_000_000;  // 1 SOL
+pub const DEFAULT_ENTERPRISE_THRESHOLD: u64 = 10_000_000_000;   // 10 SOL
+pub const DEFAULT_STAKE_VERIFICATION_THRESHOLD: u64 = 500_000_000; // 0.5 SOL, instead of paying the fee
+pub const DEFAULT_REVERIFICATION_FEE_MULTIPLIER_BPS: u16 = 20_000; // 2x for slashed or revoked agents
+pub const MAX_REVERIFICATION_FEE_MULTIPLIER_BPS: u16 = 50_000;     // 5x
+pub const WITHDRAWAL_ADDRESS_CHANGE_DELAY_SECS: i64 = 14 * 24 * 60 * 60; // 14 days, longer than the unstake cooldown
+
+pub const MAX_POLICY_MEMBERS: usize = 10;
+pub const MAX_SPONSOR_LABEL_LEN: usize = 32;
+pub const MAX_ATTESTER_NAME_LEN: usize = 32;
+pub const MAX_ATTESTER_SCHEMAS: usize = 8;
+pub const MAX_SCHEMA_DESCRIPTOR_LEN: usize = 256;
+pub const MAX_SCHEMA_FIELDS: u8 = 16;
+pub const MAX_ATTESTATION_DATA_LEN: u16 = 512;
+pub const MAX_BUILD_FRAMEWORK_LEN: usize = 32;
+pub const MAX_BUILD_MODEL_LEN: usize = 64;
+
+pub const EIP191_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+pub const WALLET_LINK_DOMAIN: &[u8] = b"SAID:link_wallet:v1";
+pub const RECEIPT_LEAF_DOMAIN: &[u8] = b"SAID:receipt:v1";
+pub const EXTERNAL_REPUTATION_DOMAIN: &[u8] = b"SAID:external_reputation:v1";
+pub const MAX_MERKLE_PROOF_LEN: usize = 32;
+// quality, reliability, speed, honesty
+pub const RATING_DIMENSIONS: usize = 4;
+pub const MAX_CATEGORY_NAME_LEN: usize = 32;
+pub const MAX_ORACLE_NAME_LEN: usize = 32;
+pub const REPUTATION_HISTORY_LEN: usize = 32;
+pub const REPUTATION_SNAPSHOT_INTERVAL_SECS: i64 = 86_400;
+// oracle score assumed while none is fresh, so the fallback never beats a neutral oracle (at most 3_750)
+pub const NEUTRAL_EXTERNAL_SCORE_BPS: u16 = 5_000;
 
 fn validate_uri(uri: &str) -> Result<()> {
     require!(
@@ -38,6 +80,619 @@ fn validate_uri(uri: &str) -> Result<()> {
     Ok(())
 }
 
+/// Verified and not past `verified_until` plus the grace period.
+/// `verified_until == None` means a verification that never expires (pre-expiry identities).
+pub fn verification_active(agent: &AgentIdentity, now: i64) -> bool {
+    match agent.verified_until {
+        Some(until) => agent.is_verified && now <= until.saturating_add(VERIFICATION_GRACE_PERIOD_SECS),
+        None => agent.is_verified,
+    }
+}
+
+/// Positive share of the decayed feedback weight in bps (0 with no feedback),
+/// plus the confidence-aware score under the given prior
+fn refresh_reputation_score(reputation: &mut AgentReputation, prior: &ReputationPrior) {
+    let total = reputation.decayed_positive as u128 + reputation.decayed_negative as u128;
+    reputation.reputation_score = positive_share_bps(reputation.decayed_positive as u128, total);
+    reputation.confidence_score = confidence_score(reputation.decayed_positive, reputation.decayed_negative, prior);
+}
+
+/// `positive / total` in bps, 0 when there is no weight; rounded, so equal verdicts seconds
+/// apart still read as an even split
+fn positive_share_bps(positive: u128, total: u128) -> u16 {
+    (positive * 10_000 + total / 2).checked_div(total).map_or(0, |share| share.min(10_000) as u16)
+}
+
+/// Bayesian average in bps: the feedback is blended with `prior.weight` worth of reviews at
+/// `prior.score_bps`, so a handful of reviews stays close to the prior
+pub fn confidence_score(decayed_positive: u64, decayed_negative: u64, prior: &ReputationPrior) -> u16 {
+    let prior_mass = prior.weight as u128 * REPUTATION_DECAY_SCALE as u128;
+    let total = decayed_positive as u128 + decayed_negative as u128 + prior_mass;
+    let positive = decayed_positive as u128 * 10_000 + prior_mass * prior.score_bps as u128;
+    (positive + total / 2).checked_div(total).map_or(prior.score_bps, |score| score.min(10_000) as u16)
+}
+
+/// Prior from the config PDA, or the defaults while it is not initialized
+fn reputation_prior(config: &AccountInfo) -> Result<ReputationPrior> {
+    if config.owner != &crate::ID || config.data_is_empty() {
+        return Ok(ReputationPrior { score_bps: DEFAULT_REPUTATION_PRIOR_SCORE_BPS, weight: DEFAULT_REPUTATION_PRIOR_WEIGHT });
+    }
+    let config = ProtocolConfig::try_deserialize(&mut &config.try_borrow_data()?[..])?;
+    Ok(ReputationPrior { score_bps: config.reputation_prior_score_bps, weight: config.reputation_prior_weight })
+}
+
+/// Oracle whose scores count in the trust score, from the config PDA (None while it is not initialized)
+fn reputation_oracle(config: &AccountInfo) -> Result<Option<Pubkey>> {
+    if config.owner != &crate::ID || config.data_is_empty() {
+        return Ok(None);
+    }
+    Ok(ProtocolConfig::try_deserialize(&mut &config.try_borrow_data()?[..])?.reputation_oracle)
+}
+
+/// Bring the decayed accumulators forward to `now` (lazy R_t = R_0 * 0.5^(t / half-life))
+fn decay_reputation(reputation: &mut AgentReputation, now: i64) {
+    let factor = decay_factor(now - reputation.last_decay_ts);
+    reputation.decayed_positive = (reputation.decayed_positive as u128 * factor / FIXED_ONE) as u64;
+    reputation.decayed_negative = (reputation.decayed_negative as u128 * factor / FIXED_ONE) as u64;
+    reputation.last_decay_ts = now;
+}
+
+/// Add one verdict to the aggregate; call decay_reputation first
+fn add_feedback(reputation: &mut AgentReputation, positive: bool, weight: u32) {
+    let decayed = weight as u64 * REPUTATION_DECAY_SCALE;
+    if positive {
+        reputation.positive_feedback += 1;
+        reputation.weighted_positive += weight as u64;
+        reputation.decayed_positive += decayed;
+    } else {
+        reputation.negative_feedback += 1;
+        reputation.weighted_negative += weight as u64;
+        reputation.decayed_negative += decayed;
+    }
+}
+
+/// Cut a recorded verdict's weight by `cut`, decayed part included; call decay_reputation first.
+/// The aggregate was decayed in steps while the record's share is decayed in one, so the two can
+/// differ by rounding; the subtraction saturates and a side with no weight left is reset to zero.
+fn discount_feedback(reputation: &mut AgentReputation, record: &FeedbackRecord, cut: u32, now: i64) {
+    let decayed = (cut as u128 * REPUTATION_DECAY_SCALE as u128 * decay_factor(now - record.updated_at) / FIXED_ONE) as u64;
+    if record.positive {
+        reputation.weighted_positive -= cut as u64;
+        reputation.decayed_positive = reputation.decayed_positive.saturating_sub(decayed);
+    } else {
+        reputation.weighted_negative -= cut as u64;
+        reputation.decayed_negative = reputation.decayed_negative.saturating_sub(decayed);
+    }
+    clear_rounding_residue(reputation);
+}
+
+/// Take a recorded verdict back out, including whatever is left of it after decay; call decay_reputation
+/// first. Rounding is handled as in discount_feedback.
+fn remove_feedback(reputation: &mut AgentReputation, record: &FeedbackRecord, now: i64) {
+    let decayed = (record.weight as u128 * REPUTATION_DECAY_SCALE as u128 * decay_factor(now - record.updated_at) / FIXED_ONE) as u64;
+    if record.positive {
+        reputation.positive_feedback -= 1;
+        reputation.weighted_positive -= record.weight as u64;
+        reputation.decayed_positive = reputation.decayed_positive.saturating_sub(decayed);
+    } else {
+        reputation.negative_feedback -= 1;
+        reputation.weighted_negative -= record.weight as u64;
+        reputation.decayed_negative = reputation.decayed_negative.saturating_sub(decayed);
+    }
+    clear_rounding_residue(reputation);
+}
+
+/// Decayed weight left on a side whose lifetime weight is gone is rounding drift
+fn clear_rounding_residue(reputation: &mut AgentReputation) {
+    if reputation.weighted_positive == 0 {
+        reputation.decayed_positive = 0;
+    }
+    if reputation.weighted_negative == 0 {
+        reputation.decayed_negative = 0;
+    }
+}
+
+/// Weight of a reviewer's feedback: base, plus a tenth of their verification and stake
+/// trust points, plus up to 1x for identity age (full at FEEDBACK_MAX_AGE_DAYS)
+pub fn feedback_weight(identity: Option<&AgentIdentity>, stake: Option<&AgentStake>, now: i64) -> u32 {
+    let Some(identity) = identity else {
+        return FEEDBACK_BASE_WEIGHT;
+    };
+    let verification = if verification_active(identity, now) { verification_points(identity.verification_tier) } else { 0 };
+    let stake = stake.map_or(0, |s| stake_points(s.amount));
+    let age_days = ((now - identity.created_at).max(0) / 86_400).min(FEEDBACK_MAX_AGE_DAYS);
+    FEEDBACK_BASE_WEIGHT + verification as u32 / 10 + stake as u32 / 10 + (age_days * 100 / FEEDBACK_MAX_AGE_DAYS) as u32
+}
+
+/// Merkle leaf for one receipt between an agent and a counterparty, as committed by submit_anchor roots
+pub fn receipt_leaf(seq: u64, counterparty: &Pubkey, receipt_hash: &[u8; 32]) -> [u8; 32] {
+    solana_keccak_hasher::hashv(&[RECEIPT_LEAF_DOMAIN, &seq.to_le_bytes(), counterparty.as_ref(), receipt_hash]).to_bytes()
+}
+
+/// Root of a keccak Merkle tree with sorted pairs (no left/right flags in the proof)
+pub fn merkle_root_from_proof(leaf: [u8; 32], proof: &[[u8; 32]]) -> [u8; 32] {
+    proof.iter().fold(leaf, |node, sibling| {
+        let (a, b) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        solana_keccak_hasher::hashv(&[&a, &b]).to_bytes()
+    })
+}
+
+/// Check that `evidence` proves the reviewer actually interacted with the agent
+fn verify_interaction(proof: &InteractionProof, evidence: &AccountInfo, agent_id: &Pubkey, reviewer: &Pubkey) -> Result<()> {
+    require!(evidence.owner == &crate::ID, SaidError::InvalidInteractionProof);
+    let data = evidence.try_borrow_data()?;
+    match proof {
+        InteractionProof::Validation => {
+            let record = ValidationRecord::try_deserialize(&mut &data[..])?;
+            // validate_work is permissionless, so only a record the agent co-signed shows a real interaction
+            require!(record.agent_id == *agent_id && record.validator == *reviewer && record.agent_cosigned, SaidError::InvalidInteractionProof);
+        }
+        InteractionProof::Receipt { seq, receipt_hash, proof } => {
+            require!(proof.len() <= MAX_MERKLE_PROOF_LEN, SaidError::InvalidInteractionProof);
+            let anchor = ReceiptAnchor::try_deserialize(&mut &data[..])?;
+            require!(anchor.agent_id == *agent_id && (anchor.start_seq..=anchor.end_seq).contains(seq), SaidError::InvalidInteractionProof);
+            let root = merkle_root_from_proof(receipt_leaf(*seq, reviewer, receipt_hash), proof);
+            require!(root == anchor.root, SaidError::InvalidInteractionProof);
+        }
+    }
+    Ok(())
+}
+
+/// Shared by submit_feedback and submit_rating: one weighted verdict per (agent, reviewer)
+fn record_feedback(
+    accounts: &mut SubmitFeedback,
+    bumps: &SubmitFeedbackBumps,
+    positive: bool,
+    context: String,
+    proof: Option<InteractionProof>,
+) -> Result<()> {
+    require!(accounts.reviewer.key() != accounts.agent_identity.owner && accounts.reviewer.key() != accounts.agent_identity.authority, SaidError::CannotReviewSelf);
+    require!(context.len() <= 500, SaidError::ContextTooLong);
+    let now = Clock::get()?.unix_timestamp;
+    let proven = match &proof {
+        Some(proof) => {
+            let evidence = accounts.interaction_evidence.as_ref().ok_or(error!(SaidError::InvalidInteractionProof))?;
+            verify_interaction(proof, evidence, &accounts.agent_identity.key(), &accounts.reviewer.key())?;
+            true
+        }
+        None => false,
+    };
+    if let Some(stake) = &accounts.reviewer_stake {
+        let identity = accounts.reviewer_identity.as_ref().ok_or(error!(SaidError::ReviewerStakeMismatch))?;
+        require!(stake.agent_id == identity.key(), SaidError::ReviewerStakeMismatch);
+    }
+    let category = accounts.category.as_deref().map(|category| category.id);
+    let prior = reputation_prior(&accounts.config)?;
+    let mut weight = feedback_weight(accounts.reviewer_identity.as_deref(), accounts.reviewer_stake.as_deref(), now);
+    // an upheld dispute keeps discounting this reviewer's verdict across edits
+    weight -= (weight as u64 * accounts.feedback_record.discount_bps as u64 / 10_000) as u32;
+    let reputation = &mut accounts.agent_reputation;
+    if reputation.agent_id == Pubkey::default() {
+        reputation.agent_id = accounts.agent_identity.key();
+        reputation.bump = bumps.agent_reputation;
+    }
+    decay_reputation(reputation, now);
+    let record = &mut accounts.feedback_record;
+    let is_new = record.created_at == 0;
+    // edits stay in the category the verdict was filed under
+    require!(is_new || record.category == category, SaidError::CategoryMismatch);
+    if is_new {
+        // deactivated categories take no new verdicts, but existing ones can still be edited
+        require!(accounts.category.as_deref().is_none_or(|c| c.active), SaidError::CategoryInactive);
+        record.agent_id = reputation.agent_id;
+        record.reviewer = accounts.reviewer.key();
+        record.category = category;
+        record.created_at = now;
+        record.bump = bumps.feedback_record;
+        reputation.total_interactions += 1;
+    } else {
+        remove_feedback(reputation, record, now);
+    }
+    // an edit re-weighs the verdict by the reviewer's current standing and restarts its decay
+    add_feedback(reputation, positive, weight);
+    if category.is_some() {
+        let bucket = accounts.category_reputation.as_mut().ok_or(error!(SaidError::CategoryReputationMismatch))?;
+        decay_reputation(bucket, now);
+        if is_new {
+            bucket.total_interactions += 1;
+        } else {
+            remove_feedback(bucket, record, now);
+        }
+        add_feedback(bucket, positive, weight);
+        refresh_reputation_score(bucket, &prior);
+        bucket.last_updated = now;
+    }
+    record.positive = positive;
+    record.weight = weight;
+    record.updated_at = now;
+    // once proven, an interaction stays proven across edits
+    record.verified_interaction |= proven;
+    require!(!accounts.agent_identity.require_interaction_proof || record.verified_interaction, SaidError::InteractionProofRequired);
+    refresh_reputation_score(reputation, &prior);
+    reputation.last_updated = now;
+    emit!(FeedbackSubmitted {
+        agent_id: reputation.agent_id,
+        from: accounts.reviewer.key(),
+        positive,
+        context,
+        new_score: reputation.reputation_score,
+        verified_interaction: record.verified_interaction,
+    });
+    Ok(())
+}
+
+/// Add (or remove) one reviewer's ratings to the per-dimension sums and counts; 0 = not rated
+fn adjust_ratings(totals: &mut AgentRatings, ratings: &[u8; RATING_DIMENSIONS], add: bool) {
+    for (i, rating) in ratings.iter().enumerate().filter(|(_, r)| **r > 0) {
+        if add {
+            totals.sums[i] += *rating as u64;
+            totals.counts[i] += 1;
+        } else {
+            totals.sums[i] -= *rating as u64;
+            totals.counts[i] -= 1;
+        }
+    }
+}
+
+/// Per-dimension average in hundredths (425 = 4.25 stars), 0 where nobody rated
+pub fn rating_averages(totals: &AgentRatings) -> [u16; RATING_DIMENSIONS] {
+    let mut averages = [0u16; RATING_DIMENSIONS];
+    for (i, average) in averages.iter_mut().enumerate() {
+        *average = (totals.sums[i] * 100).checked_div(totals.counts[i]).unwrap_or(0) as u16;
+    }
+    averages
+}
+
+/// Verification fee for this agent: the configured multiplier applies once it has been slashed or revoked
+pub fn verification_fee(agent: &AgentIdentity, config: &ProtocolConfig) -> u64 {
+    if agent.slash_count > 0 || agent.verification_revocations > 0 {
+        (VERIFICATION_FEE as u128 * config.reverification_fee_multiplier_bps as u128 / 10_000) as u64
+    } else {
+        VERIFICATION_FEE
+    }
+}
+
+/// Tier an identity qualifies for right now: without an active verification it is
+/// Registered, otherwise the highest stake threshold it meets
+pub fn tier_for(agent: &AgentIdentity, config: &ProtocolConfig, now: i64) -> VerificationTier {
+    if !verification_active(agent, now) {
+        return VerificationTier::Registered;
+    }
+    match agent.stake_amount {
+        s if s >= config.enterprise_threshold => VerificationTier::Enterprise,
+        s if s >= config.professional_threshold => VerificationTier::Professional,
+        s if s >= config.secured_threshold => VerificationTier::Secured,
+        _ => VerificationTier::Verified,
+    }
+}
+
+/// The one place tiers change; run after every stake or verification change.
+/// Also drops stake-backed verification once the stake falls below the configured level.
+fn recalculate_tier(agent: &mut Account<AgentIdentity>, config: &ProtocolConfig, now: i64) {
+    if agent.verification_source == Some(VerificationSource::Stake) && agent.stake_amount < config.stake_verification_threshold {
+        agent.verification_source = None;
+        agent.is_verified = false;
+        emit!(StakeVerificationLost { agent_id: agent.key(), stake_amount: agent.stake_amount });
+    }
+    let new_tier = tier_for(agent, config, now);
+    if new_tier != agent.verification_tier {
+        emit!(TierChanged { agent_id: agent.key(), old_tier: agent.verification_tier, new_tier });
+        agent.verification_tier = new_tier;
+    }
+}
+
+/// Not revoked and not past `expires_at` (None = no expiry)
+pub fn attestation_active(attestation: &Attestation, now: i64) -> bool {
+    match attestation.expires_at {
+        Some(e) => !attestation.revoked && now < e,
+        None => !attestation.revoked,
+    }
+}
+
+/// Checks attestation fields against the schema and returns keccak of their
+/// Borsh encoding (each field as a length-prefixed byte vector)
+pub fn attestation_data_hash(schema: &Schema, data: &[Vec<u8>]) -> Result<[u8; 32]> {
+    require!(data.len() == schema.field_count as usize, SaidError::SchemaFieldCountMismatch);
+    let size: usize = data.iter().map(Vec::len).sum();
+    require!(size <= schema.data_size as usize, SaidError::SchemaDataTooLarge);
+    let lens: Vec<[u8; 4]> = data.iter().map(|f| (f.len() as u32).to_le_bytes()).collect();
+    let mut parts: Vec<&[u8]> = Vec::with_capacity(data.len() * 2);
+    for (len, field) in lens.iter().zip(data) {
+        parts.push(len);
+        parts.push(field);
+    }
+    Ok(solana_keccak_hasher::hashv(&parts).to_bytes())
+}
+
+/// Message an EVM wallet signs (EIP-191 personal_sign) to prove it belongs to `agent_id`
+pub fn evm_link_message(agent_id: &Pubkey, nonce: u64) -> String {
+    format!("SAID Protocol: link EVM wallet\nagent: {}\nnonce: {}", agent_id, nonce)
+}
+
+/// Recover the 20-byte EVM address that produced `signature` over an EIP-191 message
+fn recover_evm_address(message: &[u8], signature: &[u8; 64], recovery_id: u8) -> Result<[u8; 20]> {
+    let len = message.len().to_string();
+    let digest = solana_keccak_hasher::hashv(&[EIP191_PREFIX, len.as_bytes(), message]);
+    // Accept both raw (0/1) and Ethereum-style (27/28) recovery ids, nothing else
+    let recovery_id = match recovery_id {
+        0 | 1 => recovery_id,
+        27 | 28 => recovery_id - 27,
+        _ => return err!(SaidError::InvalidRecoveryId),
+    };
+    let pubkey = solana_secp256k1_recover::secp256k1_recover(digest.as_ref(), recovery_id, signature)
+        .map_err(|_| error!(SaidError::InvalidEvmSignature))?;
+    let pubkey_hash = solana_keccak_hasher::hash(&pubkey.to_bytes()).to_bytes();
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&pubkey_hash[12..]);
+    Ok(address)
+}
+
+/// Message a wallet signs off-chain (ed25519) to consent to being linked to `agent_id`
+pub fn wallet_link_message(agent_id: &Pubkey, wallet: &Pubkey, nonce: u64, expires_at: i64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(WALLET_LINK_DOMAIN.len() + 32 * 3 + 16);
+    message.extend_from_slice(WALLET_LINK_DOMAIN);
+    message.extend_from_slice(crate::ID.as_ref());
+    message.extend_from_slice(agent_id.as_ref());
+    message.extend_from_slice(wallet.as_ref());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message.extend_from_slice(&expires_at.to_le_bytes());
+    message
+}
+
+/// Message an oracle signs (ed25519) to report an external reputation score for `agent_id`
+pub fn external_reputation_message(agent_id: &Pubkey, score_bps: u16, observed_at: i64, nonce: u64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(EXTERNAL_REPUTATION_DOMAIN.len() + 32 * 2 + 18);
+    message.extend_from_slice(EXTERNAL_REPUTATION_DOMAIN);
+    message.extend_from_slice(crate::ID.as_ref());
+    message.extend_from_slice(agent_id.as_ref());
+    message.extend_from_slice(&score_bps.to_le_bytes());
+    message.extend_from_slice(&observed_at.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message
+}
+
+/// Check that the instruction right before this one is an Ed25519 program
+/// verification of exactly `message` signed by `signer`
+fn verify_ed25519_ix(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current = solana_instructions_sysvar::load_current_index_checked(instructions)?;
+    require!(current > 0, SaidError::MissingEd25519Instruction);
+    let ix = solana_instructions_sysvar::load_instruction_at_checked(current as usize - 1, instructions)?;
+    require!(ix.program_id == solana_sdk_ids::ed25519_program::ID, SaidError::MissingEd25519Instruction);
+
+    // Layout: [num_signatures: u8, padding: u8, Ed25519SignatureOffsets (7 x u16)]
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, SaidError::InvalidEd25519Instruction);
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    let (signature_offset, signature_ix) = (read_u16(2), read_u16(4));
+    let (pubkey_offset, pubkey_ix) = (read_u16(6), read_u16(8));
+    let (message_offset, message_size, message_ix) = (read_u16(10), read_u16(12), read_u16(14));
+    // Everything must live inside the Ed25519 instruction itself
+    let this_ix = u16::MAX as usize;
+    require!(signature_ix == this_ix && pubkey_ix == this_ix && message_ix == this_ix, SaidError::InvalidEd25519Instruction);
+    require!(data.len() >= signature_offset + 64, SaidError::InvalidEd25519Instruction);
+    require!(data.get(pubkey_offset..pubkey_offset + 32) == Some(signer.as_ref()), SaidError::InvalidEd25519Instruction);
+    require!(data.get(message_offset..message_offset + message_size) == Some(message), SaidError::InvalidEd25519Instruction);
+    Ok(())
+}
+
+/// Check a proposed authority policy: sane threshold, no duplicate members, and every member is
+/// either the current authority or a wallet linked to this identity (its `WalletLink` in `links`)
+fn validate_policy_members(agent_id: &Pubkey, authority: &Pubkey, threshold: u8, members: &[Pubkey], links: &[AccountInfo]) -> Result<()> {
+    require!(!members.is_empty() && members.len() <= MAX_POLICY_MEMBERS, SaidError::InvalidPolicy);
+    require!(threshold >= 1 && threshold as usize <= members.len(), SaidError::InvalidPolicy);
+    for (i, member) in members.iter().enumerate() {
+        require!(!members[..i].contains(member), SaidError::InvalidPolicy);
+        require!(is_current_member(agent_id, authority, member, links)?, SaidError::WalletNotLinked);
+    }
+    Ok(())
+}
+
+/// A policy member still speaks for the identity if it is the current authority or
+/// its `WalletLink` (passed in `links`) still points at this agent.
+fn is_current_member(agent_id: &Pubkey, authority: &Pubkey, member: &Pubkey, links: &[AccountInfo]) -> Result<bool> {
+    if member == authority {
+        return Ok(true);
+    }
+    let (link_key, _) = Pubkey::find_program_address(&[b"wallet", member.as_ref()], &crate::ID);
+    let Some(link_info) = links.iter().find(|info| info.key() == link_key) else {
+        return Ok(false);
+    };
+    if link_info.owner != &crate::ID || link_info.data_is_empty() {
+        return Ok(false);
+    }
+    let link = WalletLink::try_deserialize(&mut &link_info.try_borrow_data()?[..])?;
+    Ok(link.agent_id == *agent_id)
+}
+
+/// Gate a sensitive instruction on the identity's authority policy, if it has one.
+/// Marks the matching proposal executed so it cannot be replayed. Only approvals from
+/// wallets that are still members (authority or linked, see `links`) count.
+fn consume_policy_approval(
+    agent: &Account<AgentIdentity>,
+    policy: Option<&AuthorityPolicy>,
+    proposal: Option<&mut AuthorityProposal>,
+    action: PolicyAction,
+    links: &[AccountInfo],
+) -> Result<()> {
+    if !agent.has_authority_policy {
+        return Ok(());
+    }
+    let (Some(policy), Some(proposal)) = (policy, proposal) else {
+        return err!(SaidError::PolicyApprovalRequired);
+    };
+    require!(proposal.agent_id == agent.key(), SaidError::ProposalMismatch);
+    require!(!proposal.executed, SaidError::ProposalAlreadyExecuted);
+    require!(proposal.policy_version == policy.version, SaidError::StaleProposal);
+    require!(proposal.action == action, SaidError::ProposalMismatch);
+    let mut live_approvals = 0usize;
+    for approver in proposal.approvals.iter() {
+        if policy.members.contains(approver) && is_current_member(&agent.key(), &agent.authority, approver, links)? {
+            live_approvals += 1;
+        }
+    }
+    require!(live_approvals >= policy.threshold as usize, SaidError::ThresholdNotMet);
+    proposal.executed = true;
+    emit!(PolicyActionExecuted { agent_id: agent.key(), index: proposal.index });
+    Ok(())
+}
+
+/// Sponsored onboarding: the treasury authority is unmetered, anyone else needs an
+/// approved, unexpired `Sponsor` with quota left. Returns whether a sponsor was charged.
+fn use_sponsor_quota(sponsor: Option<&mut Sponsor>, signer: &Pubkey, now: i64, registration: bool) -> Result<bool> {
+    if *signer == TREASURY_AUTHORITY {
+        return Ok(false);
+    }
+    let sponsor = sponsor.ok_or(error!(SaidError::NotApprovedSponsor))?;
+    require!(sponsor.wallet == *signer, SaidError::NotApprovedSponsor);
+    require!(now < sponsor.expires_at, SaidError::SponsorExpired);
+    let (used, quota) = if registration {
+        (&mut sponsor.registrations_used, sponsor.registration_quota)
+    } else {
+        (&mut sponsor.verifications_used, sponsor.verification_quota)
+    };
+    require!(*used < quota, SaidError::SponsorQuotaExceeded);
+    *used += 1;
+    Ok(true)
+}
+
+// ============ TRUST SCORE ============
+// Fixed-point only, so the program and off-chain clients compute identical scores.
+// Scores are in hundredths of a point: 10_000 = 100.00.
+
+const FIXED_ONE: u128 = 1_000_000_000_000;
+const LOG10_2: u128 = 301_029_995_664; // log10(2) * FIXED_ONE
+
+/// 2^(-1 / 2^i) for i = 1..=20, scaled by FIXED_ONE
+const HALF_LIFE_ROOTS: [u128; 20] = [
+    707_106_781_187, 840_896_415_254, 917_004_043_205, 957_603_280_699, 978_572_062_088,
+    989_228_013_194, 994_599_423_484, 997_296_056_085, 998_647_112_891, 999_323_327_503,
+    999_661_606_496, 999_830_788_932, 999_915_390_887, 999_957_694_548, 999_978_847_050,
+    999_989_423_469, 999_994_711_721, 999_997_355_857, 999_998_677_928, 999_999_338_964,
+];
+
+/// log10(num / den) scaled by FIXED_ONE; requires num >= den > 0
+fn log10_fixed(num: u64, den: u64) -> u128 {
+    let ratio = ((num as u128) << 32) / den as u128; // Q32, >= 1.0
+    let int_part = ratio.ilog2() - 32;
+    let mut y = ratio >> int_part; // mantissa in [1, 2)
+    let mut log2 = int_part as u128 * FIXED_ONE;
+    let mut bit = FIXED_ONE / 2;
+    for _ in 0..32 {
+        y = (y * y) >> 32;
+        if y >= 2 << 32 {
+            y >>= 1;
+            log2 += bit;
+        }
+        bit /= 2;
+    }
+    log2 * LOG10_2 / FIXED_ONE
+}
+
+/// 0.5^(elapsed / REPUTATION_HALF_LIFE_SECS) scaled by FIXED_ONE
+pub fn decay_factor(elapsed: i64) -> u128 {
+    if elapsed <= 0 {
+        return FIXED_ONE;
+    }
+    let halvings = elapsed / REPUTATION_HALF_LIFE_SECS;
+    if halvings >= 40 {
+        return 0;
+    }
+    let frac = ((elapsed % REPUTATION_HALF_LIFE_SECS) as u128) << 20;
+    let frac = (frac / REPUTATION_HALF_LIFE_SECS as u128) as u32;
+    let mut factor = FIXED_ONE;
+    for (i, root) in HALF_LIFE_ROOTS.iter().enumerate() {
+        if frac & (1 << (19 - i)) != 0 {
+            factor = factor * root / FIXED_ONE;
+        }
+    }
+    factor >> halvings
+}
+
+/// 0-25 points by tier (0 / 10 / 15 / 20 / 25)
+pub fn verification_points(tier: VerificationTier) -> u16 {
+    match tier {
+        VerificationTier::Registered => 0,
+        VerificationTier::Verified => 1_000,
+        VerificationTier::Secured => 1_500,
+        VerificationTier::Professional => 2_000,
+        VerificationTier::Enterprise => 2_500,
+    }
+}
+
+/// 15 + 2.5 * log10(stake / 0.1 SOL), capped at 25; nothing below the minimum stake
+pub fn stake_points(lamports: u64) -> u16 {
+    if lamports < MIN_STAKE_LAMPORTS {
+        return 0;
+    }
+    let points = 1_500 + (250 * log10_fixed(lamports, MIN_STAKE_LAMPORTS) + FIXED_ONE / 2) / FIXED_ONE;
+    points.min(2_500) as u16
+}
+
+/// Reputation as of `now`, without writing: the decayed accumulators brought forward, and the scores derived from them
+pub fn reputation_at(reputation: &AgentReputation, prior: &ReputationPrior, now: i64) -> ReputationView {
+    let factor = decay_factor(now - reputation.last_decay_ts);
+    let decayed_positive = (reputation.decayed_positive as u128 * factor / FIXED_ONE) as u64;
+    let decayed_negative = (reputation.decayed_negative as u128 * factor / FIXED_ONE) as u64;
+    ReputationView {
+        score: positive_share_bps(decayed_positive as u128, decayed_positive as u128 + decayed_negative as u128),
+        decayed_score: decayed_score(reputation, now),
+        confidence_score: confidence_score(decayed_positive, decayed_negative, prior),
+        decayed_positive,
+        decayed_negative,
+    }
+}
+
+/// Positive weight decayed to `now` over the total as of the last feedback, in bps: the score
+/// fading with time since the last feedback (halving every REPUTATION_HALF_LIFE_SECS)
+pub fn decayed_score(reputation: &AgentReputation, now: i64) -> u16 {
+    let factor = decay_factor(now - reputation.last_decay_ts);
+    let total = reputation.decayed_positive as u128 + reputation.decayed_negative as u128;
+    positive_share_bps(reputation.decayed_positive as u128 * factor / FIXED_ONE, total)
+}
+
+/// Snapshots go in fixed REPUTATION_SNAPSHOT_INTERVAL_SECS windows since the epoch, one per window,
+/// so whoever cranks first cannot pick an off-grid cadence; `last` is 0 before the first snapshot
+pub fn snapshot_due(last: i64, now: i64) -> bool {
+    now.div_euclid(REPUTATION_SNAPSHOT_INTERVAL_SECS) > last.div_euclid(REPUTATION_SNAPSHOT_INTERVAL_SECS)
+}
+
+/// Snapshot in effect at `timestamp`: the latest one taken at or before it (None if older than the history)
+pub fn snapshot_at(history: &ReputationHistory, timestamp: i64) -> Option<ReputationSnapshot> {
+    (1..=history.count as usize)
+        .map(|back| history.snapshots[(history.head as usize + REPUTATION_HISTORY_LEN - back) % REPUTATION_HISTORY_LEN])
+        .find(|snapshot| snapshot.timestamp <= timestamp)
+}
+
+/// 0-50 points from the decayed score (halving every REPUTATION_HALF_LIFE_SECS without feedback)
+pub fn reputation_points(reputation: &AgentReputation, now: i64) -> u16 {
+    decayed_score(reputation, now) / 2
+}
+
+/// Trust score as defined in SLASHING-SPEC-V2; verification only counts while active.
+/// Reputation is split evenly between SAID feedback and a fresh score from the pinned oracle.
+/// Without one (oracle outage, no import yet) the same blend uses NEUTRAL_EXTERNAL_SCORE_BPS, or the
+/// stale score if lower, and never exceeds SAID alone; letting a score go stale cannot raise it.
+pub fn trust_score(
+    agent: &AgentIdentity,
+    stake: Option<&AgentStake>,
+    reputation: Option<&AgentReputation>,
+    external: Option<&ExternalReputation>,
+    now: i64,
+) -> TrustScore {
+    let verification = if verification_active(agent, now) { verification_points(agent.verification_tier) } else { 0 };
+    let stake = stake.map_or(0, |s| stake_points(s.amount));
+    let said = reputation.map_or(0, |r| reputation_points(r, now));
+    let fresh = external.filter(|e| now <= e.stale_after);
+    let reputation = match fresh {
+        Some(external) => said / 2 + external.score_bps.min(10_000) / 4,
+        None => {
+            let assumed = external.map_or(NEUTRAL_EXTERNAL_SCORE_BPS, |e| e.score_bps.min(NEUTRAL_EXTERNAL_SCORE_BPS));
+            said.min(said / 2 + assumed / 4)
+        }
+    };
+    TrustScore { total: verification + stake + reputation, verification, stake, reputation, oracle_fallback: fresh.is_none() }
+}
+
 #[program]
 pub mod said {
     use super::*;
@@ -51,6 +706,81 @@ pub mod said {
         Ok(())
     }
 
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.secured_threshold = DEFAULT_SECURED_THRESHOLD;
+        config.professional_threshold = DEFAULT_PROFESSIONAL_THRESHOLD;
+        config.enterprise_threshold = DEFAULT_ENTERPRISE_THRESHOLD;
+        config.stake_verification_threshold = DEFAULT_STAKE_VERIFICATION_THRESHOLD;
+        config.reverification_fee_multiplier_bps = DEFAULT_REVERIFICATION_FEE_MULTIPLIER_BPS;
+        config.arbiter = None;
+        config.reputation_oracle = None;
+        config.reputation_prior_score_bps = DEFAULT_REPUTATION_PRIOR_SCORE_BPS;
+        config.reputation_prior_weight = DEFAULT_REPUTATION_PRIOR_WEIGHT;
+        config.build_schema = None;
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    /// Admin: set the stake (lamports) needed for Secured, Professional and Enterprise
+    pub fn set_tier_thresholds(ctx: Context<UpdateConfig>, secured: u64, professional: u64, enterprise: u64) -> Result<()> {
+        require!(secured > 0 && secured <= professional && professional <= enterprise, SaidError::InvalidTierThresholds);
+        let config = &mut ctx.accounts.config;
+        config.secured_threshold = secured;
+        config.professional_threshold = professional;
+        config.enterprise_threshold = enterprise;
+        emit!(TierThresholdsUpdated { secured, professional, enterprise });
+        Ok(())
+    }
+
+    /// Admin: stake needed for verify_by_stake; agents below a raised level lose verification on their next stake change
+    pub fn set_stake_verification_threshold(ctx: Context<UpdateConfig>, lamports: u64) -> Result<()> {
+        require!(lamports >= MIN_STAKE_LAMPORTS, SaidError::StakeTooLow);
+        ctx.accounts.config.stake_verification_threshold = lamports;
+        emit!(StakeVerificationThresholdUpdated { lamports });
+        Ok(())
+    }
+
+    /// Admin: Bayesian prior for confidence_score (score in bps, weight in baseline-review units of 100)
+    pub fn set_reputation_prior(ctx: Context<UpdateConfig>, score_bps: u16, weight: u32) -> Result<()> {
+        require!(score_bps <= 10_000, SaidError::InvalidReputationPrior);
+        require!(weight > 0 && weight <= MAX_PRIOR_WEIGHT, SaidError::InvalidPriorWeight);
+        let config = &mut ctx.accounts.config;
+        config.reputation_prior_score_bps = score_bps;
+        config.reputation_prior_weight = weight;
+        emit!(ReputationPriorUpdated { score_bps, weight });
+        Ok(())
+    }
+
+    /// Admin: wallet that may resolve feedback disputes besides the treasury authority (None = admin only)
+    pub fn set_arbiter(ctx: Context<UpdateConfig>, arbiter: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.config.arbiter = arbiter;
+        emit!(ArbiterUpdated { arbiter });
+        Ok(())
+    }
+
+    /// Admin: oracle (signing key) whose ExternalReputation counts in compute_trust_score (None = SAID only)
+    pub fn set_reputation_oracle(ctx: Context<UpdateConfig>, oracle: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.config.reputation_oracle = oracle;
+        emit!(ReputationOracleUpdated { oracle });
+        Ok(())
+    }
+
+    /// Admin: schema a build attestation must use (None = builds cannot be attested)
+    pub fn set_build_schema(ctx: Context<UpdateConfig>, schema_id: Option<[u8; 32]>) -> Result<()> {
+        ctx.accounts.config.build_schema = schema_id;
+        emit!(BuildSchemaUpdated { schema_id });
+        Ok(())
+    }
+
+    /// Admin: fee multiplier (bps, 10_000 = 1x) for re-verifying slashed or revoked agents
+    pub fn set_reverification_fee_multiplier(ctx: Context<UpdateConfig>, multiplier_bps: u16) -> Result<()> {
+        require!((10_000..=MAX_REVERIFICATION_FEE_MULTIPLIER_BPS).contains(&multiplier_bps), SaidError::InvalidFeeMultiplier);
+        ctx.accounts.config.reverification_fee_multiplier_bps = multiplier_bps;
+        emit!(ReverificationFeeMultiplierUpdated { multiplier_bps });
+        Ok(())
+    }
+
     pub fn register_agent(ctx: Context<RegisterAgent>, metadata_uri: String) -> Result<()> {
         validate_uri(&metadata_uri)?;
         let agent = &mut ctx.accounts.agent_identity;
@@ -59,38 +789,111 @@ pub mod said {
         agent.metadata_uri = metadata_uri;
         agent.created_at = Clock::get()?.unix_timestamp;
         agent.is_verified = false;
-        agent.verification_tier = 0;
+        agent.verification_tier = VerificationTier::Registered;
         agent.stake_amount = 0;
         agent.staked_at = None;
         agent.slash_count = 0;
         agent.last_slashed_at = None;
         agent.last_receipt_seq = 0;
         agent.last_anchor_index = 0;
+        agent.link_nonce = 0;
+        agent.has_authority_policy = false;
+        agent.sponsor = None;
+        agent.pending_claim = false;
+        agent.verification_source = None;
+        agent.verification_revocations = 0;
+        agent.build_count = 0;
+        agent.require_interaction_proof = false;
         agent.bump = ctx.bumps.agent_identity;
         emit!(AgentRegistered { agent_id: agent.key(), owner: agent.owner, metadata_uri: agent.metadata_uri.clone() });
         Ok(())
     }
 
     pub fn get_verified(ctx: Context<GetVerified>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        // An active verification is extended with renew_verification, not bought twice
+        require!(!verification_active(&ctx.accounts.agent_identity, now), SaidError::AlreadyVerified);
+        let fee = verification_fee(&ctx.accounts.agent_identity, &ctx.accounts.config);
         system_program::transfer(
             CpiContext::new(
                 ctx.accounts.system_program.to_account_info(),
                 system_program::Transfer { from: ctx.accounts.authority.to_account_info(), to: ctx.accounts.treasury.to_account_info() },
             ),
-            VERIFICATION_FEE,
+            fee,
         )?;
         let treasury = &mut ctx.accounts.treasury;
-        treasury.total_collected += VERIFICATION_FEE;
+        treasury.total_collected += fee;
         let agent = &mut ctx.accounts.agent_identity;
         agent.is_verified = true;
-        agent.verified_at = Some(Clock::get()?.unix_timestamp);
-        emit!(AgentVerified { agent_id: agent.key(), fee_paid: VERIFICATION_FEE });
+        agent.verified_at = Some(now);
+        agent.verified_until = Some(now + VERIFICATION_PERIOD_SECS);
+        agent.verification_source = Some(VerificationSource::Paid);
+        recalculate_tier(agent, &ctx.accounts.config, now);
+        emit!(AgentVerified { agent_id: agent.key(), fee_paid: fee });
+        Ok(())
+    }
+
+    /// Extend an active (or in-grace) paid or sponsored verification by another period at the current fee
+    pub fn renew_verification(ctx: Context<RenewVerification>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(verification_active(&ctx.accounts.agent_identity, now), SaidError::VerificationExpired);
+        // Stake-backed verification lasts as long as the stake; None predates sources and was paid
+        require!(
+            matches!(ctx.accounts.agent_identity.verification_source, None | Some(VerificationSource::Paid) | Some(VerificationSource::Sponsored)),
+            SaidError::NotRenewable
+        );
+        let fee = verification_fee(&ctx.accounts.agent_identity, &ctx.accounts.config);
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer { from: ctx.accounts.authority.to_account_info(), to: ctx.accounts.treasury.to_account_info() },
+            ),
+            fee,
+        )?;
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.total_collected += fee;
+        let agent = &mut ctx.accounts.agent_identity;
+        // Renewing inside the grace period keeps continuity from the old deadline
+        let verified_until = match agent.verified_until {
+            Some(until) => until + VERIFICATION_PERIOD_SECS,
+            None => now + VERIFICATION_PERIOD_SECS,
+        };
+        agent.verified_until = Some(verified_until);
+        agent.verification_source = Some(VerificationSource::Paid);
+        emit!(VerificationRenewed { agent_id: agent.key(), verified_until, fee_paid: fee });
+        Ok(())
+    }
+
+    /// Admin: withdraw an agent's verification with a reason; later re-verification pays the multiplied fee.
+    /// The unused part of the verification period is not refunded.
+    pub fn revoke_verification(ctx: Context<RevokeVerification>, reason: RevocationReason) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let agent = &mut ctx.accounts.agent_identity;
+        require!(agent.is_verified, SaidError::NotVerified);
+        emit!(VerificationRevoked { agent_id: agent.key(), reason, source: agent.verification_source });
+        agent.is_verified = false;
+        agent.verification_source = None;
+        agent.verification_revocations = agent.verification_revocations.saturating_add(1);
+        recalculate_tier(agent, &ctx.accounts.config, now);
+        Ok(())
+    }
+
+    /// Permissionless crank: drop verification once it is past expiry and the grace period
+    pub fn expire_verification(ctx: Context<ExpireVerification>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let agent = &mut ctx.accounts.agent_identity;
+        require!(agent.is_verified && !verification_active(agent, now), SaidError::VerificationStillActive);
+        agent.is_verified = false;
+        agent.verification_source = None;
+        recalculate_tier(agent, &ctx.accounts.config, now);
+        emit!(VerificationExpired { agent_id: agent.key(), verified_until: agent.verified_until.unwrap_or_default() });
         Ok(())
     }
 
-    pub fn register_and_stake(ctx: Context<RegisterAndStake>, metadata_uri: String, stake_lamports: u64) -> Result<()> {
+    pub fn register_and_stake(ctx: Context<RegisterAndStake>, metadata_uri: String, stake_lamports: u64, withdrawal_address: Pubkey) -> Result<()> {
         validate_uri(&metadata_uri)?;
         require!(stake_lamports >= MIN_STAKE_LAMPORTS, SaidError::StakeTooLow);
+        require!(withdrawal_address != Pubkey::default(), SaidError::InvalidWithdrawalAddress);
         let now = Clock::get()?.unix_timestamp;
         let agent = &mut ctx.accounts.agent_identity;
         agent.owner = ctx.accounts.owner.key();
@@ -99,13 +902,22 @@ pub mod said {
         agent.created_at = now;
         agent.is_verified = true;
         agent.verified_at = Some(now);
-        agent.verification_tier = 1;
+        agent.verified_until = Some(now + VERIFICATION_PERIOD_SECS);
+        agent.verification_tier = VerificationTier::Registered;
         agent.stake_amount = stake_lamports;
         agent.staked_at = Some(now);
         agent.slash_count = 0;
         agent.last_slashed_at = None;
         agent.last_receipt_seq = 0;
         agent.last_anchor_index = 0;
+        agent.link_nonce = 0;
+        agent.has_authority_policy = false;
+        agent.sponsor = None;
+        agent.pending_claim = false;
+        agent.verification_source = Some(VerificationSource::Paid);
+        agent.verification_revocations = 0;
+        agent.build_count = 0;
+        agent.require_interaction_proof = false;
         agent.bump = ctx.bumps.agent_identity;
         // Collect verification fee
         system_program::transfer(
@@ -131,7 +943,11 @@ pub mod said {
         stake.staked_at = now;
         stake.cooldown_until = None;
         stake.is_slashed = false;
+        stake.withdrawal_address = withdrawal_address;
+        stake.pending_withdrawal_address = None;
+        stake.withdrawal_change_at = None;
         stake.bump = ctx.bumps.agent_stake;
+        recalculate_tier(agent, &ctx.accounts.config, now);
         emit!(StakeDeposited { agent_id: agent.key(), amount: stake_lamports });
         emit!(AgentRegistered { agent_id: agent.key(), owner: agent.owner, metadata_uri: agent.metadata_uri.clone() });
         emit!(AgentVerified { agent_id: agent.key(), fee_paid: VERIFICATION_FEE });
@@ -151,12 +967,63 @@ pub mod said {
 
     pub fn update_agent(ctx: Context<UpdateAgent>, new_metadata_uri: String) -> Result<()> {
         validate_uri(&new_metadata_uri)?;
+        consume_policy_approval(
+            &ctx.accounts.agent_identity,
+            ctx.accounts.authority_policy.as_deref(),
+            ctx.accounts.policy_proposal.as_deref_mut(),
+            PolicyAction::UpdateAgent { new_metadata_uri: new_metadata_uri.clone() },
+            ctx.remaining_accounts,
+        )?;
         let agent = &mut ctx.accounts.agent_identity;
         agent.metadata_uri = new_metadata_uri.clone();
         emit!(AgentUpdated { agent_id: agent.key(), new_metadata_uri });
         Ok(())
     }
 
+    /// Declare a new build (framework, model, code/image hash); earlier versions stay on-chain
+    pub fn declare_build(
+        ctx: Context<DeclareBuild>,
+        framework: String,
+        model: String,
+        code_hash: [u8; 32],
+        build_uri: Option<String>,
+    ) -> Result<()> {
+        require!(!framework.is_empty() && framework.len() <= MAX_BUILD_FRAMEWORK_LEN, SaidError::InvalidBuild);
+        require!(!model.is_empty() && model.len() <= MAX_BUILD_MODEL_LEN, SaidError::InvalidBuild);
+        if let Some(uri) = &build_uri {
+            validate_uri(uri)?;
+        }
+        let now = Clock::get()?.unix_timestamp;
+        let agent = &mut ctx.accounts.agent_identity;
+        let build = &mut ctx.accounts.agent_build;
+        build.agent_id = agent.key();
+        build.version = agent.build_count;
+        build.framework = framework.clone();
+        build.model = model.clone();
+        build.code_hash = code_hash;
+        build.build_uri = build_uri;
+        build.attestation = None;
+        build.declared_at = now;
+        build.bump = ctx.bumps.agent_build;
+        agent.build_count += 1;
+        emit!(BuildDeclared { agent_id: agent.key(), version: build.version, framework, model, code_hash });
+        Ok(())
+    }
+
+    /// Point a build at a third-party attestation (e.g. a reproducible-build check) about this agent.
+    /// The attestation must use the configured build schema and its data hash must be the build's code_hash.
+    pub fn attach_build_attestation(ctx: Context<AttachBuildAttestation>) -> Result<()> {
+        let attestation = &ctx.accounts.attestation;
+        require!(ctx.accounts.config.build_schema == Some(attestation.schema_id), SaidError::NotBuildSchema);
+        require!(attestation.data_hash == ctx.accounts.agent_build.code_hash, SaidError::BuildCodeHashMismatch);
+        require!(!attestation.revoked, SaidError::AttestationRevoked);
+        require!(attestation_active(attestation, Clock::get()?.unix_timestamp), SaidError::AttestationExpired);
+        let build = &mut ctx.accounts.agent_build;
+        build.attestation = Some(attestation.key());
+        emit!(BuildAttestationAttached { agent_id: build.agent_id, version: build.version, attestation: attestation.key(), attester: attestation.attester });
+        Ok(())
+    }
+
     pub fn link_wallet(ctx: Context<LinkWallet>) -> Result<()> {
         let wallet_link = &mut ctx.accounts.wallet_link;
         wallet_link.agent_id = ctx.accounts.agent_identity.key();
@@ -166,12 +1033,77 @@ pub mod said {
         Ok(())
     }
 
+    /// Link a wallet that consented off-chain via a preceding Ed25519 program instruction
+    pub fn link_wallet_with_signature(ctx: Context<LinkWalletWithSignature>, nonce: u64, expires_at: i64) -> Result<()> {
+        require!(Clock::get()?.unix_timestamp <= expires_at, SaidError::SignatureExpired);
+        let agent = &mut ctx.accounts.agent_identity;
+        require!(nonce == agent.link_nonce, SaidError::InvalidLinkNonce);
+        let wallet = ctx.accounts.new_wallet.key();
+        let message = wallet_link_message(&agent.key(), &wallet, nonce, expires_at);
+        verify_ed25519_ix(&ctx.accounts.instructions.to_account_info(), &wallet, &message)?;
+        agent.link_nonce = agent.link_nonce.saturating_add(1);
+
+        let wallet_link = &mut ctx.accounts.wallet_link;
+        wallet_link.agent_id = agent.key();
+        wallet_link.wallet = wallet;
+        wallet_link.bump = ctx.bumps.wallet_link;
+        emit!(WalletLinked { agent_id: agent.key(), wallet, linked_by: ctx.accounts.authority.key() });
+        Ok(())
+    }
+
     pub fn unlink_wallet(ctx: Context<UnlinkWallet>) -> Result<()> {
         emit!(WalletUnlinked { agent_id: ctx.accounts.agent_identity.key(), wallet: ctx.accounts.wallet_link.wallet, unlinked_by: ctx.accounts.caller.key() });
         Ok(())
     }
 
+    /// Add a shared wallet as a member of this identity. Unlike `link_wallet`, a wallet can be
+    /// a member of many identities, and membership grants no recovery powers.
+    pub fn add_wallet_membership(ctx: Context<AddWalletMembership>) -> Result<()> {
+        let membership = &mut ctx.accounts.wallet_membership;
+        membership.agent_id = ctx.accounts.agent_identity.key();
+        membership.wallet = ctx.accounts.wallet.key();
+        membership.joined_at = Clock::get()?.unix_timestamp;
+        membership.bump = ctx.bumps.wallet_membership;
+        emit!(WalletMembershipAdded { agent_id: membership.agent_id, wallet: membership.wallet, added_by: ctx.accounts.authority.key() });
+        Ok(())
+    }
+
+    pub fn remove_wallet_membership(ctx: Context<RemoveWalletMembership>) -> Result<()> {
+        emit!(WalletMembershipRemoved { agent_id: ctx.accounts.agent_identity.key(), wallet: ctx.accounts.wallet_membership.wallet, removed_by: ctx.accounts.caller.key() });
+        Ok(())
+    }
+
+    /// Link an EVM address by verifying an EIP-191 signature over the agent id and current link nonce
+    pub fn link_evm_wallet(ctx: Context<LinkEvmWallet>, evm_address: [u8; 20], nonce: u64, signature: [u8; 64], recovery_id: u8) -> Result<()> {
+        let agent = &mut ctx.accounts.agent_identity;
+        require!(nonce == agent.link_nonce, SaidError::InvalidLinkNonce);
+        let message = evm_link_message(&agent.key(), nonce);
+        let recovered = recover_evm_address(message.as_bytes(), &signature, recovery_id)?;
+        require!(recovered == evm_address, SaidError::InvalidEvmSignature);
+        agent.link_nonce = agent.link_nonce.saturating_add(1);
+
+        let evm_link = &mut ctx.accounts.evm_wallet_link;
+        evm_link.agent_id = agent.key();
+        evm_link.evm_address = evm_address;
+        evm_link.linked_at = Clock::get()?.unix_timestamp;
+        evm_link.bump = ctx.bumps.evm_wallet_link;
+        emit!(EvmWalletLinked { agent_id: agent.key(), evm_address, linked_by: ctx.accounts.authority.key() });
+        Ok(())
+    }
+
+    pub fn unlink_evm_wallet(ctx: Context<UnlinkEvmWallet>) -> Result<()> {
+        emit!(EvmWalletUnlinked { agent_id: ctx.accounts.agent_identity.key(), evm_address: ctx.accounts.evm_wallet_link.evm_address, unlinked_by: ctx.accounts.authority.key() });
+        Ok(())
+    }
+
     pub fn transfer_authority(ctx: Context<TransferAuthority>) -> Result<()> {
+        consume_policy_approval(
+            &ctx.accounts.agent_identity,
+            ctx.accounts.authority_policy.as_deref(),
+            ctx.accounts.policy_proposal.as_deref_mut(),
+            PolicyAction::TransferAuthority { new_authority: ctx.accounts.new_authority.key() },
+            ctx.remaining_accounts,
+        )?;
         let old_authority = ctx.accounts.agent_identity.authority;
         let agent = &mut ctx.accounts.agent_identity;
         agent.authority = ctx.accounts.new_authority.key();
@@ -179,47 +1111,560 @@ pub mod said {
         Ok(())
     }
 
+    /// Require `threshold` of `members` (the authority and/or linked wallets) to approve
+    /// sensitive operations. Pass each non-authority member's `WalletLink` as a remaining account.
+    pub fn create_authority_policy(ctx: Context<CreateAuthorityPolicy>, threshold: u8, members: Vec<Pubkey>) -> Result<()> {
+        let agent = &mut ctx.accounts.agent_identity;
+        require!(!agent.has_authority_policy, SaidError::PolicyAlreadyExists);
+        validate_policy_members(&agent.key(), &agent.authority, threshold, &members, ctx.remaining_accounts)?;
+        let policy = &mut ctx.accounts.authority_policy;
+        if policy.agent_id == Pubkey::default() {
+            policy.agent_id = agent.key();
+            policy.version = 0;
+            policy.proposal_count = 0;
+            policy.bump = ctx.bumps.authority_policy;
+        } else {
+            // Re-enabling a removed policy: keep proposal numbering, invalidate old proposals
+            policy.version += 1;
+        }
+        policy.threshold = threshold;
+        policy.members = members;
+        agent.has_authority_policy = true;
+        emit!(AuthorityPolicyUpdated { agent_id: agent.key(), threshold, members: policy.members.clone(), version: policy.version });
+        Ok(())
+    }
+
+    pub fn propose_policy_action(ctx: Context<ProposePolicyAction>, action: PolicyAction) -> Result<()> {
+        match &action {
+            PolicyAction::UpdateAgent { new_metadata_uri } => validate_uri(new_metadata_uri)?,
+            PolicyAction::UpdatePolicy { members, .. } => require!(members.len() <= MAX_POLICY_MEMBERS, SaidError::InvalidPolicy),
+            _ => {}
+        }
+        let policy = &mut ctx.accounts.authority_policy;
+        let proposal = &mut ctx.accounts.policy_proposal;
+        proposal.agent_id = policy.agent_id;
+        proposal.index = policy.proposal_count;
+        proposal.policy_version = policy.version;
+        proposal.action = action.clone();
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.approvals = vec![ctx.accounts.proposer.key()];
+        proposal.created_at = Clock::get()?.unix_timestamp;
+        proposal.executed = false;
+        proposal.bump = ctx.bumps.policy_proposal;
+        policy.proposal_count += 1;
+        emit!(PolicyActionProposed { agent_id: proposal.agent_id, index: proposal.index, proposer: proposal.proposer, action });
+        Ok(())
+    }
+
+    pub fn approve_policy_action(ctx: Context<ApprovePolicyAction>) -> Result<()> {
+        let policy = &ctx.accounts.authority_policy;
+        let proposal = &mut ctx.accounts.policy_proposal;
+        require!(!proposal.executed, SaidError::ProposalAlreadyExecuted);
+        require!(proposal.policy_version == policy.version, SaidError::StaleProposal);
+        let member = ctx.accounts.member.key();
+        require!(!proposal.approvals.contains(&member), SaidError::AlreadyApproved);
+        proposal.approvals.push(member);
+        emit!(PolicyActionApproved { agent_id: proposal.agent_id, index: proposal.index, member, approvals: proposal.approvals.len() as u8 });
+        Ok(())
+    }
+
+    /// Close an executed or stale proposal and reclaim its rent (proposer only)
+    pub fn close_policy_proposal(ctx: Context<ClosePolicyProposal>) -> Result<()> {
+        let proposal = &ctx.accounts.policy_proposal;
+        // Pending proposals stay until executed or invalidated by a policy change
+        require!(proposal.executed || proposal.policy_version != ctx.accounts.authority_policy.version, SaidError::ProposalStillPending);
+        emit!(PolicyProposalClosed { agent_id: ctx.accounts.policy_proposal.agent_id, index: ctx.accounts.policy_proposal.index });
+        Ok(())
+    }
+
+    /// Replace the policy's threshold and member set (requires an approved `UpdatePolicy` proposal)
+    pub fn update_authority_policy(ctx: Context<UpdateAuthorityPolicy>, threshold: u8, members: Vec<Pubkey>) -> Result<()> {
+        let agent = &ctx.accounts.agent_identity;
+        validate_policy_members(&agent.key(), &agent.authority, threshold, &members, ctx.remaining_accounts)?;
+        consume_policy_approval(
+            agent,
+            Some(&ctx.accounts.authority_policy),
+            Some(&mut ctx.accounts.policy_proposal),
+            PolicyAction::UpdatePolicy { threshold, members: members.clone() },
+            ctx.remaining_accounts,
+        )?;
+        let policy = &mut ctx.accounts.authority_policy;
+        policy.threshold = threshold;
+        policy.members = members;
+        // Outstanding proposals were approved under the old member set
+        policy.version += 1;
+        emit!(AuthorityPolicyUpdated { agent_id: agent.key(), threshold, members: policy.members.clone(), version: policy.version });
+        Ok(())
+    }
+
+    /// Drop back to single-signer authority (requires an approved `RemovePolicy` proposal)
+    pub fn remove_authority_policy(ctx: Context<RemoveAuthorityPolicy>) -> Result<()> {
+        consume_policy_approval(
+            &ctx.accounts.agent_identity,
+            Some(&ctx.accounts.authority_policy),
+            Some(&mut ctx.accounts.policy_proposal),
+            PolicyAction::RemovePolicy,
+            ctx.remaining_accounts,
+        )?;
+        // The policy account stays so proposal PDAs are never reused; bumping the version
+        // invalidates anything still pending
+        ctx.accounts.authority_policy.version += 1;
+        let agent = &mut ctx.accounts.agent_identity;
+        agent.has_authority_policy = false;
+        emit!(AuthorityPolicyRemoved { agent_id: agent.key() });
+        Ok(())
+    }
+
+    /// Register an identity on behalf of `agent_wallet` (treasury authority or an approved sponsor)
     pub fn sponsor_register(ctx: Context<SponsorRegister>, metadata_uri: String) -> Result<()> {
         validate_uri(&metadata_uri)?;
+        let now = Clock::get()?.unix_timestamp;
+        use_sponsor_quota(ctx.accounts.sponsor.as_deref_mut(), &ctx.accounts.authority.key(), now, true)?;
         let agent = &mut ctx.accounts.agent_identity;
         agent.owner = ctx.accounts.agent_wallet.key();
         agent.authority = ctx.accounts.agent_wallet.key();
         agent.metadata_uri = metadata_uri;
-        agent.created_at = Clock::get()?.unix_timestamp;
+        agent.created_at = now;
         agent.is_verified = false;
-        agent.verification_tier = 0;
+        agent.verification_tier = VerificationTier::Registered;
         agent.stake_amount = 0;
         agent.staked_at = None;
         agent.slash_count = 0;
         agent.last_slashed_at = None;
         agent.last_receipt_seq = 0;
         agent.last_anchor_index = 0;
+        agent.link_nonce = 0;
+        agent.has_authority_policy = false;
+        agent.sponsor = Some(ctx.accounts.authority.key());
+        // the agent wallet never signed; it must accept (or reject) the identity
+        agent.pending_claim = true;
+        agent.verification_source = None;
+        agent.verification_revocations = 0;
+        agent.build_count = 0;
+        agent.require_interaction_proof = false;
         agent.bump = ctx.bumps.agent_identity;
         emit!(AgentRegistered { agent_id: agent.key(), owner: agent.owner, metadata_uri: agent.metadata_uri.clone() });
+        emit!(AgentSponsored { agent_id: agent.key(), sponsor: ctx.accounts.authority.key() });
         Ok(())
     }
 
-    pub fn sponsor_verify(ctx: Context<SponsorVerify>) -> Result<()> {
+    /// Agent wallet takes ownership of a sponsored identity, optionally replacing its metadata
+    pub fn accept_sponsored_identity(ctx: Context<AcceptSponsoredIdentity>, new_metadata_uri: Option<String>) -> Result<()> {
         let agent = &mut ctx.accounts.agent_identity;
-        agent.is_verified = true;
-        agent.verified_at = Some(Clock::get()?.unix_timestamp);
-        emit!(AgentVerified { agent_id: agent.key(), fee_paid: 0 });
+        require!(agent.pending_claim, SaidError::NotPendingClaim);
+        if let Some(uri) = new_metadata_uri {
+            validate_uri(&uri)?;
+            agent.metadata_uri = uri.clone();
+            emit!(AgentUpdated { agent_id: agent.key(), new_metadata_uri: uri });
+        }
+        agent.pending_claim = false;
+        emit!(SponsoredIdentityAccepted { agent_id: agent.key(), owner: agent.owner });
         Ok(())
     }
 
-    pub fn submit_feedback(ctx: Context<SubmitFeedback>, positive: bool, context: String) -> Result<()> {
-        require!(ctx.accounts.reviewer.key() != ctx.accounts.agent_identity.owner && ctx.accounts.reviewer.key() != ctx.accounts.agent_identity.authority, SaidError::CannotReviewSelf);
-        require!(context.len() <= 500, SaidError::ContextTooLong);
-        let reputation = &mut ctx.accounts.agent_reputation;
-        if reputation.total_interactions == 0 {
-            reputation.agent_id = ctx.accounts.agent_identity.key();
-            reputation.bump = ctx.bumps.agent_reputation;
+    /// Agent wallet declines a sponsored identity; the account is closed back to the sponsor
+    pub fn reject_sponsored_identity(ctx: Context<RejectSponsoredIdentity>) -> Result<()> {
+        let agent = &ctx.accounts.agent_identity;
+        require!(agent.pending_claim, SaidError::NotPendingClaim);
+        require!(agent.stake_amount == 0, SaidError::AlreadyStaked);
+        emit!(SponsoredIdentityRejected { agent_id: agent.key(), owner: agent.owner, sponsor: ctx.accounts.sponsor_wallet.key() });
+        Ok(())
+    }
+
+    /// Verify an identity on someone's behalf. Approved sponsors pay the verification fee and
+    /// may only verify identities they registered; the treasury authority does not pay.
+    pub fn sponsor_verify(ctx: Context<SponsorVerify>) -> Result<()> {
+        let signer = ctx.accounts.authority.key();
+        require!(signer == TREASURY_AUTHORITY || ctx.accounts.agent_identity.sponsor == Some(signer), SaidError::NotIdentitySponsor);
+        require!(!ctx.accounts.agent_identity.pending_claim, SaidError::ClaimPending);
+        let now = Clock::get()?.unix_timestamp;
+        let charged = use_sponsor_quota(ctx.accounts.sponsor.as_deref_mut(), &ctx.accounts.authority.key(), now, false)?;
+        let fee = if charged { verification_fee(&ctx.accounts.agent_identity, &ctx.accounts.config) } else { 0 };
+        if fee > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer { from: ctx.accounts.authority.to_account_info(), to: ctx.accounts.treasury.to_account_info() },
+                ),
+                fee,
+            )?;
+            ctx.accounts.treasury.total_collected += fee;
         }
-        reputation.total_interactions += 1;
-        if positive { reputation.positive_feedback += 1; } else { reputation.negative_feedback += 1; }
-        reputation.reputation_score = ((reputation.positive_feedback as u128 * 10000) / reputation.total_interactions as u128).min(10000) as u16;
-        reputation.last_updated = Clock::get()?.unix_timestamp;
-        emit!(FeedbackSubmitted { agent_id: reputation.agent_id, from: ctx.accounts.reviewer.key(), positive, context, new_score: reputation.reputation_score });
+        let agent = &mut ctx.accounts.agent_identity;
+        agent.is_verified = true;
+        agent.verified_at = Some(now);
+        agent.verified_until = Some(now + VERIFICATION_PERIOD_SECS);
+        agent.verification_source = Some(VerificationSource::Sponsored);
+        recalculate_tier(agent, &ctx.accounts.config, now);
+        emit!(AgentVerified { agent_id: agent.key(), fee_paid: fee });
+        Ok(())
+    }
+
+    /// Admin: approve a partner platform to sponsor registrations and verifications
+    pub fn approve_sponsor(ctx: Context<ApproveSponsor>, label: String, registration_quota: u32, verification_quota: u32, expires_at: i64) -> Result<()> {
+        require!(!label.is_empty() && label.len() <= MAX_SPONSOR_LABEL_LEN, SaidError::InvalidSponsorLabel);
+        let now = Clock::get()?.unix_timestamp;
+        require!(expires_at > now, SaidError::SponsorExpired);
+        let sponsor = &mut ctx.accounts.sponsor;
+        sponsor.wallet = ctx.accounts.sponsor_wallet.key();
+        sponsor.label = label.clone();
+        sponsor.registration_quota = registration_quota;
+        sponsor.registrations_used = 0;
+        sponsor.verification_quota = verification_quota;
+        sponsor.verifications_used = 0;
+        sponsor.expires_at = expires_at;
+        sponsor.approved_at = now;
+        sponsor.bump = ctx.bumps.sponsor;
+        emit!(SponsorApproved { sponsor: sponsor.wallet, label, registration_quota, verification_quota, expires_at });
+        Ok(())
+    }
+
+    /// Admin: change a sponsor's quotas or expiry (used counters are kept)
+    pub fn update_sponsor(ctx: Context<UpdateSponsor>, registration_quota: u32, verification_quota: u32, expires_at: i64) -> Result<()> {
+        require!(expires_at > Clock::get()?.unix_timestamp, SaidError::SponsorExpired);
+        let sponsor = &mut ctx.accounts.sponsor;
+        sponsor.registration_quota = registration_quota;
+        sponsor.verification_quota = verification_quota;
+        sponsor.expires_at = expires_at;
+        emit!(SponsorUpdated { sponsor: sponsor.wallet, registration_quota, verification_quota, expires_at });
+        Ok(())
+    }
+
+    pub fn revoke_sponsor(ctx: Context<RevokeSponsor>) -> Result<()> {
+        emit!(SponsorRevoked { sponsor: ctx.accounts.sponsor.wallet });
+        Ok(())
+    }
+
+    /// Admin: allow a wallet (KYC provider, auditor, platform) to issue the given schemas
+    pub fn register_attester(ctx: Context<RegisterAttester>, name: String, schemas: Vec<[u8; 32]>) -> Result<()> {
+        require!(!name.is_empty() && name.len() <= MAX_ATTESTER_NAME_LEN, SaidError::InvalidAttesterName);
+        require!(schemas.len() <= MAX_ATTESTER_SCHEMAS, SaidError::TooManySchemas);
+        let attester = &mut ctx.accounts.attester;
+        attester.wallet = ctx.accounts.attester_wallet.key();
+        attester.name = name.clone();
+        attester.schemas = schemas.clone();
+        attester.registered_at = Clock::get()?.unix_timestamp;
+        attester.bump = ctx.bumps.attester;
+        emit!(AttesterRegistered { attester: attester.wallet, name, schemas });
+        Ok(())
+    }
+
+    /// Admin: replace the schemas an attester may issue (existing attestations are untouched)
+    pub fn update_attester_schemas(ctx: Context<UpdateAttester>, schemas: Vec<[u8; 32]>) -> Result<()> {
+        require!(schemas.len() <= MAX_ATTESTER_SCHEMAS, SaidError::TooManySchemas);
+        let attester = &mut ctx.accounts.attester;
+        attester.schemas = schemas.clone();
+        emit!(AttesterUpdated { attester: attester.wallet, schemas });
+        Ok(())
+    }
+
+    pub fn revoke_attester(ctx: Context<RevokeAttester>) -> Result<()> {
+        emit!(AttesterRevoked { attester: ctx.accounts.attester.wallet });
+        Ok(())
+    }
+
+    /// Creator publishes a schema under a global id; the admin co-signs so ids cannot be squatted.
+    /// Attesters still need admin approval to issue it.
+    pub fn register_schema(
+        ctx: Context<RegisterSchema>,
+        id: [u8; 32],
+        descriptor: String,
+        field_count: u8,
+        data_size: u16,
+        revocable: bool,
+        resolver: ResolverPolicy,
+    ) -> Result<()> {
+        require!(!descriptor.is_empty() && descriptor.len() <= MAX_SCHEMA_DESCRIPTOR_LEN, SaidError::InvalidSchema);
+        require!(field_count > 0 && field_count <= MAX_SCHEMA_FIELDS, SaidError::InvalidSchema);
+        require!(data_size > 0 && data_size <= MAX_ATTESTATION_DATA_LEN, SaidError::InvalidSchema);
+        let schema = &mut ctx.accounts.schema;
+        schema.id = id;
+        schema.creator = ctx.accounts.creator.key();
+        schema.descriptor = descriptor;
+        schema.field_count = field_count;
+        schema.data_size = data_size;
+        schema.revocable = revocable;
+        schema.resolver = resolver;
+        schema.created_at = Clock::get()?.unix_timestamp;
+        schema.bump = ctx.bumps.schema;
+        emit!(SchemaRegistered { id, creator: schema.creator, field_count, data_size, revocable, resolver });
+        Ok(())
+    }
+
+    /// Issues a new attestation, or reissues one this attester revoked earlier
+    pub fn issue_attestation(ctx: Context<IssueAttestation>, schema_id: [u8; 32], data: Vec<Vec<u8>>, uri: String, expires_at: Option<i64>) -> Result<()> {
+        let existing = &ctx.accounts.attestation;
+        require!(existing.agent_id == Pubkey::default() || existing.revoked, SaidError::AttestationExists);
+        require!(ctx.accounts.attester.schemas.contains(&schema_id), SaidError::SchemaNotAllowed);
+        let schema = &ctx.accounts.schema;
+        if schema.resolver == ResolverPolicy::CreatorOnly {
+            require!(ctx.accounts.authority.key() == schema.creator, SaidError::SchemaNotAllowed);
+        }
+        let data_hash = attestation_data_hash(schema, &data)?;
+        validate_uri(&uri)?;
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(e) = expires_at {
+            require!(e > now, SaidError::InvalidAttestationExpiry);
+        }
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.agent_id = ctx.accounts.agent_identity.key();
+        attestation.attester = ctx.accounts.authority.key();
+        attestation.schema_id = schema_id;
+        attestation.data_hash = data_hash;
+        attestation.uri = uri;
+        attestation.issued_at = now;
+        attestation.updated_at = now;
+        attestation.expires_at = expires_at;
+        attestation.revoked = false;
+        attestation.revoked_at = None;
+        attestation.bump = ctx.bumps.attestation;
+        emit!(AttestationIssued { agent_id: attestation.agent_id, attester: attestation.attester, schema_id, data_hash, expires_at });
+        Ok(())
+    }
+
+    /// Attester refreshes the data, URI or expiry of a live attestation
+    pub fn update_attestation(ctx: Context<UpdateAttestation>, data: Vec<Vec<u8>>, uri: String, expires_at: Option<i64>) -> Result<()> {
+        let attestation = &mut ctx.accounts.attestation;
+        require!(!attestation.revoked, SaidError::AttestationRevoked);
+        require!(ctx.accounts.attester.schemas.contains(&attestation.schema_id), SaidError::SchemaNotAllowed);
+        let data_hash = attestation_data_hash(&ctx.accounts.schema, &data)?;
+        validate_uri(&uri)?;
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(e) = expires_at {
+            require!(e > now, SaidError::InvalidAttestationExpiry);
+        }
+        attestation.data_hash = data_hash;
+        attestation.uri = uri;
+        attestation.updated_at = now;
+        attestation.expires_at = expires_at;
+        emit!(AttestationUpdated { agent_id: attestation.agent_id, attester: attestation.attester, schema_id: attestation.schema_id, data_hash, expires_at });
+        Ok(())
+    }
+
+    /// Original attester can always revoke, even after being deregistered
+    pub fn revoke_attestation(ctx: Context<RevokeAttestation>) -> Result<()> {
+        require!(ctx.accounts.schema.revocable, SaidError::SchemaNotRevocable);
+        let attestation = &mut ctx.accounts.attestation;
+        require!(!attestation.revoked, SaidError::AttestationRevoked);
+        attestation.revoked = true;
+        attestation.revoked_at = Some(Clock::get()?.unix_timestamp);
+        emit!(AttestationRevoked { agent_id: attestation.agent_id, attester: attestation.attester, schema_id: attestation.schema_id });
+        Ok(())
+    }
+
+    /// Thumbs-up/down feedback, kept alongside submit_rating for existing clients.
+    /// One verdict per reviewer: re-submitting replaces the previous verdict instead of adding another.
+    /// `proof` + `interaction_evidence` flag the feedback as a verified interaction (required if the agent opted in).
+    pub fn submit_feedback(ctx: Context<SubmitFeedback>, positive: bool, context: String, proof: Option<InteractionProof>) -> Result<()> {
+        record_feedback(ctx.accounts, &ctx.bumps, positive, context, proof)
+    }
+
+    /// 1-5 ratings for quality, reliability, speed and honesty (0 = not rated). Also counts as a
+    /// thumbs-up (mean >= 3) or thumbs-down in the boolean aggregate, replacing any earlier verdict.
+    pub fn submit_rating(ctx: Context<SubmitRating>, ratings: [u8; RATING_DIMENSIONS], context: String, proof: Option<InteractionProof>) -> Result<()> {
+        require!(ratings.iter().all(|r| *r <= 5) && ratings.iter().any(|r| *r > 0), SaidError::InvalidRating);
+        let sum: u32 = ratings.iter().map(|r| *r as u32).sum();
+        let count = ratings.iter().filter(|r| **r > 0).count() as u32;
+        let previous = ctx.accounts.feedback.feedback_record.ratings;
+        record_feedback(&mut ctx.accounts.feedback, &ctx.bumps.feedback, sum >= 3 * count, context, proof)?;
+        let totals = &mut ctx.accounts.agent_ratings;
+        if totals.agent_id == Pubkey::default() {
+            totals.agent_id = ctx.accounts.feedback.agent_identity.key();
+            totals.bump = ctx.bumps.agent_ratings;
+        }
+        adjust_ratings(totals, &previous, false);
+        adjust_ratings(totals, &ratings, true);
+        totals.last_updated = Clock::get()?.unix_timestamp;
+        ctx.accounts.feedback.feedback_record.ratings = ratings;
+        emit!(RatingSubmitted { agent_id: totals.agent_id, from: ctx.accounts.feedback.reviewer.key(), ratings, averages: rating_averages(totals) });
+        Ok(())
+    }
+
+    /// Agent opts in (or out) of accepting only feedback backed by a proven interaction
+    pub fn set_feedback_policy(ctx: Context<SetFeedbackPolicy>, require_interaction_proof: bool) -> Result<()> {
+        let agent = &mut ctx.accounts.agent_identity;
+        agent.require_interaction_proof = require_interaction_proof;
+        emit!(FeedbackPolicyUpdated { agent_id: agent.key(), require_interaction_proof });
+        Ok(())
+    }
+
+    /// Reviewer takes their verdict out of the aggregate and closes the record; not while a dispute over it is open or upheld, so a ruling cannot be undone
+    pub fn withdraw_feedback(ctx: Context<WithdrawFeedback>) -> Result<()> {
+        let dispute = &ctx.accounts.feedback_dispute;
+        if dispute.owner == &crate::ID {
+            let dispute = FeedbackDispute::try_deserialize(&mut &dispute.try_borrow_data()?[..])?;
+            require!(dispute.status == DisputeStatus::Rejected, SaidError::FeedbackUnderDispute);
+        }
+        let record = &ctx.accounts.feedback_record;
+        if record.ratings.iter().any(|r| *r > 0) {
+            let totals = ctx.accounts.agent_ratings.as_deref_mut().ok_or(error!(SaidError::InvalidRating))?;
+            adjust_ratings(totals, &record.ratings, false);
+        }
+        let now = Clock::get()?.unix_timestamp;
+        let prior = reputation_prior(&ctx.accounts.config)?;
+        if record.category.is_some() {
+            let bucket = ctx.accounts.category_reputation.as_mut().ok_or(error!(SaidError::CategoryReputationMismatch))?;
+            decay_reputation(bucket, now);
+            bucket.total_interactions -= 1;
+            remove_feedback(bucket, record, now);
+            refresh_reputation_score(bucket, &prior);
+            bucket.last_updated = now;
+        }
+        let reputation = &mut ctx.accounts.agent_reputation;
+        decay_reputation(reputation, now);
+        reputation.total_interactions -= 1;
+        remove_feedback(reputation, record, now);
+        refresh_reputation_score(reputation, &prior);
+        reputation.last_updated = now;
+        emit!(FeedbackWithdrawn { agent_id: reputation.agent_id, from: record.reviewer, positive: record.positive, new_score: reputation.reputation_score });
+        Ok(())
+    }
+
+    /// Agent authority contests a negative feedback record with a public response
+    pub fn open_feedback_dispute(ctx: Context<OpenFeedbackDispute>, response_uri: String) -> Result<()> {
+        validate_uri(&response_uri)?;
+        let record = &ctx.accounts.feedback_record;
+        require!(!record.positive, SaidError::FeedbackNotNegative);
+        let dispute = &mut ctx.accounts.feedback_dispute;
+        if dispute.agent_id != Pubkey::default() {
+            // only a verdict filed again after a rejected dispute can be contested again
+            let refiled = dispute.resolved_at.is_some_and(|resolved| record.created_at > resolved);
+            require!(dispute.status == DisputeStatus::Rejected && refiled, SaidError::DisputeAlreadyResolved);
+        }
+        dispute.agent_id = record.agent_id;
+        dispute.reviewer = record.reviewer;
+        dispute.feedback_record = record.key();
+        dispute.response_uri = response_uri.clone();
+        dispute.status = DisputeStatus::Open;
+        dispute.discount_bps = 0;
+        dispute.opened_at = Clock::get()?.unix_timestamp;
+        dispute.resolved_at = None;
+        dispute.resolver = None;
+        dispute.bump = ctx.bumps.feedback_dispute;
+        emit!(FeedbackDisputeOpened { agent_id: dispute.agent_id, reviewer: dispute.reviewer, response_uri });
+        Ok(())
+    }
+
+    /// Admin or configured arbiter: an upheld dispute discounts the feedback's weight by
+    /// `discount_bps` (10_000 removes it) for good, including future edits by the reviewer
+    pub fn resolve_feedback_dispute(ctx: Context<ResolveFeedbackDispute>, upheld: bool, discount_bps: u16) -> Result<()> {
+        let resolver = ctx.accounts.resolver.key();
+        require!(resolver == TREASURY_AUTHORITY || ctx.accounts.config.arbiter == Some(resolver), SaidError::NotArbiter);
+        require!(ctx.accounts.feedback_dispute.status == DisputeStatus::Open, SaidError::DisputeAlreadyResolved);
+        let now = Clock::get()?.unix_timestamp;
+        let reputation = &mut ctx.accounts.agent_reputation;
+        let record = &mut ctx.accounts.feedback_record;
+        if upheld {
+            require!(discount_bps > 0 && discount_bps <= 10_000, SaidError::InvalidSeverity);
+            // the reviewer may have turned the verdict positive since; then there is nothing to uphold
+            require!(!record.positive, SaidError::FeedbackNotNegative);
+            let prior = reputation_prior(&ctx.accounts.config.to_account_info())?;
+            let cut = (record.weight as u64 * discount_bps as u64 / 10_000) as u32;
+            if record.category.is_some() {
+                let bucket = ctx.accounts.category_reputation.as_mut().ok_or(error!(SaidError::CategoryReputationMismatch))?;
+                decay_reputation(bucket, now);
+                discount_feedback(bucket, record, cut, now);
+                refresh_reputation_score(bucket, &prior);
+                bucket.last_updated = now;
+            }
+            decay_reputation(reputation, now);
+            discount_feedback(reputation, record, cut, now);
+            record.weight -= cut;
+            record.discount_bps = discount_bps;
+            refresh_reputation_score(reputation, &prior);
+            reputation.last_updated = now;
+        }
+        let dispute = &mut ctx.accounts.feedback_dispute;
+        dispute.status = if upheld { DisputeStatus::Upheld } else { DisputeStatus::Rejected };
+        dispute.discount_bps = if upheld { discount_bps } else { 0 };
+        dispute.resolved_at = Some(now);
+        dispute.resolver = Some(resolver);
+        emit!(FeedbackDisputeResolved {
+            agent_id: dispute.agent_id,
+            reviewer: dispute.reviewer,
+            upheld,
+            discount_bps: dispute.discount_bps,
+            new_score: reputation.reputation_score,
+        });
+        Ok(())
+    }
+
+    /// Read-only: reputation decayed to the current clock, via set_return_data
+    pub fn get_reputation(ctx: Context<GetReputation>) -> Result<ReputationView> {
+        let prior = reputation_prior(&ctx.accounts.config)?;
+        Ok(reputation_at(&ctx.accounts.agent_reputation, &prior, Clock::get()?.unix_timestamp))
+    }
+
+    /// Permissionless crank: append the agent's current (decayed) reputation to its history ring
+    /// buffer, at most once per aligned REPUTATION_SNAPSHOT_INTERVAL_SECS window (the first snapshot
+    /// included, see snapshot_due). The oldest entry is overwritten when full.
+    pub fn snapshot_reputation(ctx: Context<SnapshotReputation>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let reputation = &ctx.accounts.agent_reputation;
+        let history = &mut ctx.accounts.reputation_history;
+        require!(snapshot_due(history.last_snapshot_at, now), SaidError::SnapshotTooSoon);
+        if history.agent_id == Pubkey::default() {
+            history.agent_id = reputation.agent_id;
+            history.bump = ctx.bumps.reputation_history;
+        }
+        let view = reputation_at(reputation, &reputation_prior(&ctx.accounts.config)?, now);
+        let snapshot = ReputationSnapshot {
+            timestamp: now,
+            score: view.decayed_score,
+            confidence_score: view.confidence_score,
+            total_interactions: reputation.total_interactions,
+            positive_feedback: reputation.positive_feedback,
+            negative_feedback: reputation.negative_feedback,
+        };
+        let head = history.head as usize;
+        history.snapshots[head] = snapshot;
+        history.head = ((head + 1) % REPUTATION_HISTORY_LEN) as u16;
+        history.count = (history.count + 1).min(REPUTATION_HISTORY_LEN as u16);
+        history.last_snapshot_at = now;
+        emit!(ReputationSnapshotted { agent_id: history.agent_id, timestamp: now, score: snapshot.score, confidence_score: snapshot.confidence_score });
+        Ok(())
+    }
+
+    /// Read-only: the snapshot in effect at `timestamp`, via set_return_data
+    pub fn get_reputation_snapshot(ctx: Context<GetReputationSnapshot>, timestamp: i64) -> Result<ReputationSnapshot> {
+        snapshot_at(&ctx.accounts.reputation_history, timestamp).ok_or(error!(SaidError::SnapshotNotFound))
+    }
+
+    /// Read-only: an agent's reputation within one category, decayed to the current clock
+    pub fn get_category_reputation(ctx: Context<GetCategoryReputation>) -> Result<ReputationView> {
+        let prior = reputation_prior(&ctx.accounts.config)?;
+        Ok(reputation_at(&ctx.accounts.category_reputation, &prior, Clock::get()?.unix_timestamp))
+    }
+
+    /// Admin: add a feedback category (trading, support, ...)
+    pub fn register_category(ctx: Context<RegisterCategory>, id: u16, name: String) -> Result<()> {
+        require!(!name.is_empty() && name.len() <= MAX_CATEGORY_NAME_LEN, SaidError::InvalidCategoryName);
+        let category = &mut ctx.accounts.category;
+        category.id = id;
+        category.name = name.clone();
+        category.active = true;
+        category.created_at = Clock::get()?.unix_timestamp;
+        category.bump = ctx.bumps.category;
+        emit!(CategoryRegistered { id, name });
+        Ok(())
+    }
+
+    /// Admin: stop (or resume) accepting feedback in a category; existing buckets keep their scores
+    pub fn set_category_active(ctx: Context<UpdateCategory>, active: bool) -> Result<()> {
+        let category = &mut ctx.accounts.category;
+        category.active = active;
+        emit!(CategoryStatusChanged { id: category.id, active });
+        Ok(())
+    }
+
+    /// Permissionless: create an agent's reputation bucket for a category before feedback is filed in it
+    pub fn open_category_reputation(ctx: Context<OpenCategoryReputation>) -> Result<()> {
+        require!(ctx.accounts.category.active, SaidError::CategoryInactive);
+        let bucket = &mut ctx.accounts.category_reputation;
+        bucket.agent_id = ctx.accounts.agent_identity.key();
+        bucket.last_updated = Clock::get()?.unix_timestamp;
+        bucket.bump = ctx.bumps.category_reputation;
+        emit!(CategoryReputationOpened { agent_id: bucket.agent_id, category: ctx.accounts.category.id });
         Ok(())
     }
 
@@ -230,6 +1675,7 @@ pub mod said {
         validation.validator = ctx.accounts.validator.key();
         validation.task_hash = task_hash;
         validation.passed = passed;
+        validation.agent_cosigned = ctx.accounts.agent_authority.is_some();
         validation.evidence_uri = evidence_uri.clone();
         validation.timestamp = Clock::get()?.unix_timestamp;
         validation.bump = ctx.bumps.validation_record;
@@ -237,13 +1683,95 @@ pub mod said {
         Ok(())
     }
 
-    /// Stake for an already-registered agent (creates AgentStake, upgrades to tier 2)
-    pub fn stake(ctx: Context<Stake>, stake_lamports: u64) -> Result<()> {
+    /// Read-only: score is returned via set_return_data for CPI callers
+    /// The pinned oracle's ExternalReputation PDA is required once one is configured, so callers
+    /// cannot pick which oracle counts or leave a low score out
+    pub fn compute_trust_score(ctx: Context<ComputeTrustScore>) -> Result<TrustScore> {
+        let agent_id = ctx.accounts.agent_identity.key();
+        let external = match reputation_oracle(&ctx.accounts.config)? {
+            Some(oracle) => {
+                let account = ctx.accounts.external_reputation.as_ref().ok_or(error!(SaidError::ExternalReputationMismatch))?;
+                let (expected, _) = Pubkey::find_program_address(&[b"external_reputation", agent_id.as_ref(), oracle.as_ref()], &crate::ID);
+                require!(account.key() == expected, SaidError::ExternalReputationMismatch);
+                // not created yet: the oracle has never scored this agent
+                if account.owner == &crate::ID {
+                    Some(ExternalReputation::try_deserialize(&mut &account.try_borrow_data()?[..])?)
+                } else {
+                    None
+                }
+            }
+            None => None,
+        };
+        Ok(trust_score(
+            &ctx.accounts.agent_identity,
+            ctx.accounts.agent_stake.as_deref(),
+            ctx.accounts.agent_reputation.as_deref(),
+            external.as_ref(),
+            Clock::get()?.unix_timestamp,
+        ))
+    }
+
+    /// Admin: register an external reputation oracle (e.g. FairScale) by its ed25519 signing key
+    pub fn register_oracle(ctx: Context<RegisterOracle>, name: String, max_staleness_secs: i64) -> Result<()> {
+        require!(!name.is_empty() && name.len() <= MAX_ORACLE_NAME_LEN, SaidError::InvalidOracleName);
+        require!(max_staleness_secs > 0, SaidError::InvalidOracleStaleness);
+        let oracle = &mut ctx.accounts.oracle;
+        oracle.signer = ctx.accounts.oracle_signer.key();
+        oracle.name = name.clone();
+        oracle.active = true;
+        oracle.max_staleness_secs = max_staleness_secs;
+        oracle.registered_at = Clock::get()?.unix_timestamp;
+        oracle.bump = ctx.bumps.oracle;
+        emit!(OracleRegistered { signer: oracle.signer, name, max_staleness_secs });
+        Ok(())
+    }
+
+    /// Admin: pause an oracle or change how long its scores stay fresh. Scores already imported
+    /// count until their own `stale_after`.
+    pub fn update_oracle(ctx: Context<UpdateOracle>, active: bool, max_staleness_secs: i64) -> Result<()> {
+        require!(max_staleness_secs > 0, SaidError::InvalidOracleStaleness);
+        let oracle = &mut ctx.accounts.oracle;
+        oracle.active = active;
+        oracle.max_staleness_secs = max_staleness_secs;
+        emit!(OracleUpdated { signer: oracle.signer, active, max_staleness_secs });
+        Ok(())
+    }
+
+    /// Permissionless relay of an oracle score, proven by a preceding Ed25519 program instruction
+    /// over `external_reputation_message`. Nonces strictly increase per (agent, oracle).
+    pub fn update_external_reputation(ctx: Context<UpdateExternalReputation>, score_bps: u16, observed_at: i64, nonce: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let oracle = &ctx.accounts.oracle;
+        require!(oracle.active, SaidError::OracleInactive);
+        require!(score_bps <= 10_000, SaidError::InvalidExternalScore);
+        require!(observed_at <= now && now - observed_at <= oracle.max_staleness_secs, SaidError::StaleOracleUpdate);
+        let external = &mut ctx.accounts.external_reputation;
+        require!(nonce > external.nonce && observed_at >= external.observed_at, SaidError::InvalidOracleNonce);
+        let agent_id = ctx.accounts.agent_identity.key();
+        let message = external_reputation_message(&agent_id, score_bps, observed_at, nonce);
+        verify_ed25519_ix(&ctx.accounts.instructions.to_account_info(), &oracle.signer, &message)?;
+
+        external.agent_id = agent_id;
+        external.oracle = oracle.signer;
+        external.score_bps = score_bps;
+        external.observed_at = observed_at;
+        external.stale_after = observed_at + oracle.max_staleness_secs;
+        external.nonce = nonce;
+        external.updated_at = now;
+        external.bump = ctx.bumps.external_reputation;
+        emit!(ExternalReputationUpdated { agent_id, oracle: oracle.signer, score_bps, observed_at, nonce });
+        Ok(())
+    }
+
+    /// Stake for a verified agent (creates AgentStake, or reuses one left empty by an unstake;
+    /// tier follows the stake amount). Unstake payouts always go to `withdrawal_address`, whoever triggers them.
+    pub fn stake(ctx: Context<Stake>, stake_lamports: u64, withdrawal_address: Pubkey) -> Result<()> {
         require!(stake_lamports >= MIN_STAKE_LAMPORTS, SaidError::StakeTooLow);
+        require!(withdrawal_address != Pubkey::default(), SaidError::InvalidWithdrawalAddress);
         let now = Clock::get()?.unix_timestamp;
         let agent = &mut ctx.accounts.agent_identity;
-        require!(agent.is_verified, SaidError::NotVerified);
-        require!(agent.stake_amount == 0, SaidError::AlreadyStaked);
+        require!(verification_active(agent, now), SaidError::NotVerified);
+        require!(agent.stake_amount == 0 && ctx.accounts.agent_stake.amount == 0, SaidError::AlreadyStaked);
         
         system_program::transfer(
             CpiContext::new(
@@ -259,16 +1787,72 @@ pub mod said {
         stake.staked_at = now;
         stake.cooldown_until = None;
         stake.is_slashed = false;
+        stake.withdrawal_address = withdrawal_address;
+        stake.pending_withdrawal_address = None;
+        stake.withdrawal_change_at = None;
         stake.bump = ctx.bumps.agent_stake;
         
         agent.stake_amount = stake_lamports;
         agent.staked_at = Some(now);
-        agent.verification_tier = 2; // Secured tier
+        recalculate_tier(agent, &ctx.accounts.config, now);
         
         emit!(StakeDeposited { agent_id: agent.key(), amount: stake_lamports });
         Ok(())
     }
 
+    /// Verify by locking collateral instead of paying VERIFICATION_FEE (SLASHING-SPEC `stake_for_verification`).
+    /// Verification has no expiry but lasts only while the stake stays at or above the configured level.
+    /// An existing stake counts towards the threshold (`stake_lamports` is added on top) and keeps its withdrawal address.
+    pub fn verify_by_stake(ctx: Context<VerifyByStake>, stake_lamports: u64, withdrawal_address: Pubkey) -> Result<()> {
+        require!(withdrawal_address != Pubkey::default(), SaidError::InvalidWithdrawalAddress);
+        let now = Clock::get()?.unix_timestamp;
+        let agent = &mut ctx.accounts.agent_identity;
+        require!(!verification_active(agent, now), SaidError::AlreadyVerified);
+        let stake = &ctx.accounts.agent_stake;
+        let existing = stake.agent_id != Pubkey::default();
+        if existing {
+            require!(stake.cooldown_until.is_none(), SaidError::AlreadyUnstaking);
+            // payouts only move through request_withdrawal_address_change
+            require!(withdrawal_address == stake.withdrawal_address, SaidError::InvalidWithdrawalAddress);
+        }
+        let total = stake.amount.saturating_add(stake_lamports);
+        require!(total >= ctx.accounts.config.stake_verification_threshold, SaidError::StakeTooLow);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer { from: ctx.accounts.authority.to_account_info(), to: ctx.accounts.agent_stake.to_account_info() },
+            ),
+            stake_lamports,
+        )?;
+
+        let stake = &mut ctx.accounts.agent_stake;
+        if !existing {
+            stake.agent_id = agent.key();
+            stake.cooldown_until = None;
+            stake.withdrawal_address = withdrawal_address;
+            stake.pending_withdrawal_address = None;
+            stake.withdrawal_change_at = None;
+            stake.bump = ctx.bumps.agent_stake;
+        }
+        if stake.amount == 0 {
+            stake.staked_at = now;
+            agent.staked_at = Some(now);
+        }
+        stake.amount = total;
+
+        agent.stake_amount = total;
+        agent.is_verified = true;
+        agent.verified_at = Some(now);
+        agent.verified_until = None;
+        agent.verification_source = Some(VerificationSource::Stake);
+        recalculate_tier(agent, &ctx.accounts.config, now);
+
+        emit!(StakeDeposited { agent_id: agent.key(), amount: stake_lamports });
+        emit!(AgentVerified { agent_id: agent.key(), fee_paid: 0 });
+        Ok(())
+    }
+
     /// Add to existing stake (increases stake amount)
     pub fn add_stake(ctx: Context<AddStake>, additional_lamports: u64) -> Result<()> {
         require!(additional_lamports > 0, SaidError::StakeTooLow);
@@ -290,6 +1874,7 @@ pub mod said {
         
         let agent = &mut ctx.accounts.agent_identity;
         agent.stake_amount = stake.amount;
+        recalculate_tier(agent, &ctx.accounts.config, Clock::get()?.unix_timestamp);
         
         emit!(StakeDeposited { agent_id: agent.key(), amount: additional_lamports });
         Ok(())
@@ -308,21 +1893,31 @@ pub mod said {
     }
 
     pub fn complete_unstake(ctx: Context<CompleteUnstake>) -> Result<()> {
-        require!(ctx.accounts.authority.key() == ctx.accounts.agent_identity.authority, SaidError::Unauthorized);
+        // With a policy, the approved proposal authorizes the call instead of the authority
+        if !ctx.accounts.agent_identity.has_authority_policy {
+            require!(ctx.accounts.authority.key() == ctx.accounts.agent_identity.authority, SaidError::Unauthorized);
+        }
+        consume_policy_approval(
+            &ctx.accounts.agent_identity,
+            ctx.accounts.authority_policy.as_deref(),
+            ctx.accounts.policy_proposal.as_deref_mut(),
+            PolicyAction::CompleteUnstake,
+            ctx.remaining_accounts,
+        )?;
         let now = Clock::get()?.unix_timestamp;
         let amount = ctx.accounts.agent_stake.amount;
         require!(amount > 0, SaidError::NoActiveStake);
         require!(ctx.accounts.agent_stake.cooldown_until.is_some(), SaidError::UnstakeNotRequested);
         require!(now >= ctx.accounts.agent_stake.cooldown_until.unwrap(), SaidError::CooldownNotComplete);
         **ctx.accounts.agent_stake.to_account_info().try_borrow_mut_lamports()? -= amount;
-        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += amount;
+        **ctx.accounts.withdrawal_address.to_account_info().try_borrow_mut_lamports()? += amount;
         let stake = &mut ctx.accounts.agent_stake;
         stake.amount = 0;
         stake.cooldown_until = None;
         let agent = &mut ctx.accounts.agent_identity;
-        agent.verification_tier = 1;
         agent.stake_amount = 0;
         agent.staked_at = None;
+        recalculate_tier(agent, &ctx.accounts.config, now);
         emit!(Unstaked { agent_id: agent.key(), amount });
         Ok(())
     }
@@ -338,7 +1933,16 @@ pub mod said {
     }
 
     pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
-        require!(ctx.accounts.authority.key() == ctx.accounts.agent_identity.authority, SaidError::Unauthorized);
+        if !ctx.accounts.agent_identity.has_authority_policy {
+            require!(ctx.accounts.authority.key() == ctx.accounts.agent_identity.authority, SaidError::Unauthorized);
+        }
+        consume_policy_approval(
+            &ctx.accounts.agent_identity,
+            ctx.accounts.authority_policy.as_deref(),
+            ctx.accounts.policy_proposal.as_deref_mut(),
+            PolicyAction::EmergencyUnstake,
+            ctx.remaining_accounts,
+        )?;
         let amount = ctx.accounts.agent_stake.amount;
         require!(amount > 0, SaidError::NoActiveStake);
         let penalty = (amount as u128 * EMERGENCY_UNSTAKE_PENALTY_BPS as u128 / 10_000) as u64;
@@ -346,18 +1950,66 @@ pub mod said {
         **ctx.accounts.agent_stake.to_account_info().try_borrow_mut_lamports()? -= penalty;
         **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += penalty;
         **ctx.accounts.agent_stake.to_account_info().try_borrow_mut_lamports()? -= payout;
-        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += payout;
+        **ctx.accounts.withdrawal_address.to_account_info().try_borrow_mut_lamports()? += payout;
         let stake = &mut ctx.accounts.agent_stake;
         stake.amount = 0;
         stake.cooldown_until = None;
+        let now = Clock::get()?.unix_timestamp;
         let agent = &mut ctx.accounts.agent_identity;
-        agent.verification_tier = 1;
         agent.stake_amount = 0;
         agent.staked_at = None;
+        recalculate_tier(agent, &ctx.accounts.config, now);
         emit!(EmergencyUnstaked { agent_id: agent.key(), payout, penalty });
         Ok(())
     }
 
+    /// Start a delayed change of the stake's withdrawal address
+    pub fn request_withdrawal_address_change(ctx: Context<RequestWithdrawalAddressChange>, new_address: Pubkey) -> Result<()> {
+        if !ctx.accounts.agent_identity.has_authority_policy {
+            require!(ctx.accounts.authority.key() == ctx.accounts.agent_identity.authority, SaidError::Unauthorized);
+        }
+        require!(new_address != Pubkey::default(), SaidError::InvalidWithdrawalAddress);
+        consume_policy_approval(
+            &ctx.accounts.agent_identity,
+            ctx.accounts.authority_policy.as_deref(),
+            ctx.accounts.policy_proposal.as_deref_mut(),
+            PolicyAction::ChangeWithdrawalAddress { new_address },
+            ctx.remaining_accounts,
+        )?;
+        let stake = &mut ctx.accounts.agent_stake;
+        let effective_at = Clock::get()?.unix_timestamp + WITHDRAWAL_ADDRESS_CHANGE_DELAY_SECS;
+        stake.pending_withdrawal_address = Some(new_address);
+        stake.withdrawal_change_at = Some(effective_at);
+        emit!(WithdrawalAddressChangeRequested { agent_id: stake.agent_id, new_address, effective_at });
+        Ok(())
+    }
+
+    pub fn complete_withdrawal_address_change(ctx: Context<CompleteWithdrawalAddressChange>) -> Result<()> {
+        require!(ctx.accounts.authority.key() == ctx.accounts.agent_identity.authority, SaidError::Unauthorized);
+        let stake = &mut ctx.accounts.agent_stake;
+        let (Some(new_address), Some(effective_at)) = (stake.pending_withdrawal_address, stake.withdrawal_change_at) else {
+            return err!(SaidError::NoPendingWithdrawalChange);
+        };
+        require!(Clock::get()?.unix_timestamp >= effective_at, SaidError::WithdrawalChangeDelayNotElapsed);
+        let old_address = stake.withdrawal_address;
+        stake.withdrawal_address = new_address;
+        stake.pending_withdrawal_address = None;
+        stake.withdrawal_change_at = None;
+        emit!(WithdrawalAddressChanged { agent_id: stake.agent_id, old_address, new_address });
+        Ok(())
+    }
+
+    /// Authority or the current withdrawal address may cancel, so a hijacked authority
+    /// cannot redirect payouts before the rightful owner notices
+    pub fn cancel_withdrawal_address_change(ctx: Context<CancelWithdrawalAddressChange>) -> Result<()> {
+        let stake = &mut ctx.accounts.agent_stake;
+        require!(stake.pending_withdrawal_address.is_some(), SaidError::NoPendingWithdrawalChange);
+        stake.pending_withdrawal_address = None;
+        stake.withdrawal_change_at = None;
+        emit!(WithdrawalAddressChangeCancelled { agent_id: stake.agent_id, cancelled_by: ctx.accounts.caller.key() });
+        Ok(())
+    }
+
     pub fn slash_agent(ctx: Context<SlashAgent>, severity_bps: u16) -> Result<()> {
         require!(ctx.accounts.admin.key() == TREASURY_AUTHORITY, SaidError::UnauthorizedAuthority);
         require!(severity_bps <= 10_000, SaidError::InvalidSeverity);
@@ -371,22 +2023,33 @@ pub mod said {
         let stake = &mut ctx.accounts.agent_stake;
         stake.amount = remaining;
         stake.is_slashed = true;
+        let now = Clock::get()?.unix_timestamp;
         let agent = &mut ctx.accounts.agent_identity;
         agent.slash_count = agent.slash_count.saturating_add(1);
-        agent.last_slashed_at = Some(Clock::get()?.unix_timestamp);
+        agent.last_slashed_at = Some(now);
+        // A partial slash also ends a stake-backed verification once below the threshold (see recalculate_tier)
+        let below_stake_threshold = agent.verification_source == Some(VerificationSource::Stake) && remaining < ctx.accounts.config.stake_verification_threshold;
+        if agent.is_verified && (remaining == 0 || below_stake_threshold) {
+            emit!(VerificationRevoked { agent_id: agent.key(), reason: RevocationReason::Slashed, source: agent.verification_source });
+            agent.verification_revocations = agent.verification_revocations.saturating_add(1);
+        }
         if remaining == 0 {
             agent.is_verified = false;
-            agent.verification_tier = 0;
+            agent.verification_source = None;
             agent.stake_amount = 0;
             agent.staked_at = None;
         } else {
             agent.stake_amount = remaining;
         }
+        recalculate_tier(agent, &ctx.accounts.config, now);
         emit!(AgentSlashed { agent_id: agent.key(), amount: slash_amount, severity_bps });
         Ok(())
     }
 
-    /// Submit a Merkle anchor for a contiguous receipt range [start_seq, end_seq]
+    /// Submit a Merkle anchor for a contiguous receipt range [start_seq, end_seq].
+    /// Leaves are `receipt_leaf(seq, counterparty, receipt_hash)`, i.e.
+    /// keccak(RECEIPT_LEAF_DOMAIN ‖ seq as u64 LE ‖ counterparty ‖ receipt_hash), and each
+    /// parent is keccak of its two children in sorted order (see merkle_root_from_proof).
     pub fn submit_anchor(
         ctx: Context<SubmitAnchor>,
         anchor_index: u64,
@@ -444,6 +2107,77 @@ pub enum SaidError {
     #[msg("Invalid anchor range")] InvalidAnchorRange,
     #[msg("Agent must be verified before staking")] NotVerified,
     #[msg("Agent already has active stake")] AlreadyStaked,
+    #[msg("EVM signature does not recover to the given address")] InvalidEvmSignature,
+    #[msg("Link nonce does not match the identity's current nonce")] InvalidLinkNonce,
+    #[msg("Expected an Ed25519 signature instruction immediately before this one")] MissingEd25519Instruction,
+    #[msg("Ed25519 instruction does not match the expected signer or message")] InvalidEd25519Instruction,
+    #[msg("Signed message has expired")] SignatureExpired,
+    #[msg("Invalid authority policy: check threshold and members")] InvalidPolicy,
+    #[msg("Identity already has an authority policy")] PolicyAlreadyExists,
+    #[msg("Identity has no active authority policy")] NoAuthorityPolicy,
+    #[msg("Signer is not a member of the authority policy")] NotPolicyMember,
+    #[msg("This operation requires an approved policy proposal")] PolicyApprovalRequired,
+    #[msg("Proposal does not match this identity or action")] ProposalMismatch,
+    #[msg("Proposal already executed")] ProposalAlreadyExecuted,
+    #[msg("Proposal was created under an older policy version")] StaleProposal,
+    #[msg("Proposal does not have enough approvals")] ThresholdNotMet,
+    #[msg("Member already approved this proposal")] AlreadyApproved,
+    #[msg("Invalid withdrawal address")] InvalidWithdrawalAddress,
+    #[msg("No pending withdrawal address change")] NoPendingWithdrawalChange,
+    #[msg("Withdrawal address change delay has not elapsed")] WithdrawalChangeDelayNotElapsed,
+    #[msg("Signer is neither the treasury authority nor an approved sponsor")] NotApprovedSponsor,
+    #[msg("Sponsor approval has expired")] SponsorExpired,
+    #[msg("Sponsor quota exhausted")] SponsorQuotaExceeded,
+    #[msg("Sponsor label must be 1-32 characters")] InvalidSponsorLabel,
+    #[msg("Identity is not awaiting a claim")] NotPendingClaim,
+    #[msg("Verification has expired; use get_verified")] VerificationExpired,
+    #[msg("Verification is still active or within its grace period")] VerificationStillActive,
+    #[msg("Tier thresholds must be non-zero and ascending")] InvalidTierThresholds,
+    #[msg("Attester name must be 1-32 characters")] InvalidAttesterName,
+    #[msg("Too many schemas for one attester")] TooManySchemas,
+    #[msg("Attester is not allowed to issue this schema")] SchemaNotAllowed,
+    #[msg("Attestation expiry must be in the future")] InvalidAttestationExpiry,
+    #[msg("Attestation has been revoked")] AttestationRevoked,
+    #[msg("Invalid schema: check descriptor, field count and size")] InvalidSchema,
+    #[msg("Attestation field count does not match the schema")] SchemaFieldCountMismatch,
+    #[msg("Attestation data exceeds the schema's declared size")] SchemaDataTooLarge,
+    #[msg("Schema does not allow revocation")] SchemaNotRevocable,
+    #[msg("Agent already has an active verification")] AlreadyVerified,
+    #[msg("Fee multiplier must be between 1x and 5x")] InvalidFeeMultiplier,
+    #[msg("Build framework must be 1-32 and model 1-64 characters")] InvalidBuild,
+    #[msg("Attestation is not about this agent")] AttestationMismatch,
+    #[msg("Reviewer stake must belong to the reviewer's own identity")] ReviewerStakeMismatch,
+    #[msg("Interaction proof does not check out against the evidence account")] InvalidInteractionProof,
+    #[msg("This agent only accepts feedback with a proven interaction")] InteractionProofRequired,
+    #[msg("Ratings must be 0-5 with at least one dimension rated")] InvalidRating,
+    #[msg("Only negative feedback can be disputed")] FeedbackNotNegative,
+    #[msg("Signer is neither the treasury authority nor the configured arbiter")] NotArbiter,
+    #[msg("Dispute already resolved")] DisputeAlreadyResolved,
+    #[msg("Reputation prior score must be at most 10000 bps")] InvalidReputationPrior,
+    #[msg("Category name must be 1-32 bytes")] InvalidCategoryName,
+    #[msg("Category is not accepting feedback")] CategoryInactive,
+    #[msg("Feedback was filed under a different category")] CategoryMismatch,
+    #[msg("Category reputation account missing or not the agent's bucket for this category")] CategoryReputationMismatch,
+    #[msg("Oracle name must be 1-32 bytes")] InvalidOracleName,
+    #[msg("Oracle staleness limit must be positive")] InvalidOracleStaleness,
+    #[msg("Oracle is not active")] OracleInactive,
+    #[msg("External score must be at most 10000 bps")] InvalidExternalScore,
+    #[msg("Oracle update is stale or from the future")] StaleOracleUpdate,
+    #[msg("Oracle nonce must increase")] InvalidOracleNonce,
+    #[msg("Reputation was snapshotted too recently")] SnapshotTooSoon,
+    #[msg("No reputation snapshot at or before that time")] SnapshotNotFound,
+    #[msg("Attester already has a live attestation for this agent and schema")] AttestationExists,
+    #[msg("Attestation has expired")] AttestationExpired,
+    #[msg("Attestation does not use the build schema")] NotBuildSchema,
+    #[msg("Attestation data hash does not match the build's code hash")] BuildCodeHashMismatch,
+    #[msg("Feedback is under an open or upheld dispute")] FeedbackUnderDispute,
+    #[msg("External reputation account is not the pinned oracle's PDA for this agent")] ExternalReputationMismatch,
+    #[msg("EVM recovery id must be 0, 1, 27 or 28")] InvalidRecoveryId,
+    #[msg("Proposal is still pending; only executed or stale proposals can be closed")] ProposalStillPending,
+    #[msg("Sponsors can only verify identities they registered")] NotIdentitySponsor,
+    #[msg("Identity has not been claimed by its agent wallet yet")] ClaimPending,
+    #[msg("Only paid or sponsored verifications can be renewed")] NotRenewable,
+    #[msg("Reputation prior weight must be between 1 and MAX_PRIOR_WEIGHT")] InvalidPriorWeight,
 }
 
 // ============ ACCOUNTS ============
@@ -466,6 +2200,23 @@ pub struct InitializeTreasury<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(init, payer = authority, space = 8 + ProtocolConfig::INIT_SPACE, seeds = [b"config"], bump)]
+    pub config: Account<'info, ProtocolConfig>,
+    #[account(mut, address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+    #[account(address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
+    pub authority: Signer<'info>,
+}
+
 #[derive(Accounts)]
 #[instruction(metadata_uri: String)]
 pub struct RegisterAgent<'info> {
@@ -477,117 +2228,662 @@ pub struct RegisterAgent<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(metadata_uri: String)]
-pub struct RegisterAndStake<'info> {
-    #[account(init, payer = owner, space = 8 + AgentIdentity::INIT_SPACE, seeds = [b"agent", owner.key().as_ref()], bump)]
+#[instruction(metadata_uri: String)]
+pub struct RegisterAndStake<'info> {
+    #[account(init, payer = owner, space = 8 + AgentIdentity::INIT_SPACE, seeds = [b"agent", owner.key().as_ref()], bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(init, payer = owner, space = 8 + AgentStake::INIT_SPACE, seeds = [b"stake", agent_identity.key().as_ref()], bump)]
+    pub agent_stake: Account<'info, AgentStake>,
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetVerified<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RenewVerification<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireVerification<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVerification<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+    #[account(address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAgent<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(seeds = [b"policy", agent_identity.key().as_ref()], bump = authority_policy.bump)]
+    pub authority_policy: Option<Account<'info, AuthorityPolicy>>,
+    #[account(mut, seeds = [b"proposal", agent_identity.key().as_ref(), &policy_proposal.index.to_le_bytes()], bump = policy_proposal.bump)]
+    pub policy_proposal: Option<Account<'info, AuthorityProposal>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct LinkWallet<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(init, payer = authority, space = 8 + WalletLink::INIT_SPACE, seeds = [b"wallet", new_wallet.key().as_ref()], bump)]
+    pub wallet_link: Account<'info, WalletLink>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub new_wallet: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LinkWalletWithSignature<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(init, payer = authority, space = 8 + WalletLink::INIT_SPACE, seeds = [b"wallet", new_wallet.key().as_ref()], bump)]
+    pub wallet_link: Account<'info, WalletLink>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: consent is proven by the Ed25519 signature over the link message
+    pub new_wallet: UncheckedAccount<'info>,
+    /// CHECK: instructions sysvar
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnlinkWallet<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(mut, close = caller, seeds = [b"wallet", wallet_link.wallet.as_ref()], bump = wallet_link.bump, constraint = wallet_link.agent_id == agent_identity.key() @ SaidError::WalletNotLinked)]
+    pub wallet_link: Account<'info, WalletLink>,
+    #[account(mut, constraint = caller.key() == agent_identity.authority || caller.key() == wallet_link.wallet @ SaidError::Unauthorized)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeclareBuild<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(init, payer = authority, space = 8 + AgentBuild::INIT_SPACE, seeds = [b"build", agent_identity.key().as_ref(), &agent_identity.build_count.to_le_bytes()], bump)]
+    pub agent_build: Account<'info, AgentBuild>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AttachBuildAttestation<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(mut, seeds = [b"build", agent_identity.key().as_ref(), &agent_build.version.to_le_bytes()], bump = agent_build.bump)]
+    pub agent_build: Account<'info, AgentBuild>,
+    #[account(constraint = attestation.agent_id == agent_identity.key() @ SaidError::AttestationMismatch)]
+    pub attestation: Account<'info, Attestation>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddWalletMembership<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(init, payer = authority, space = 8 + WalletMembership::INIT_SPACE, seeds = [b"membership", wallet.key().as_ref(), agent_identity.key().as_ref()], bump)]
+    pub wallet_membership: Account<'info, WalletMembership>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub wallet: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveWalletMembership<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(mut, close = caller, seeds = [b"membership", wallet_membership.wallet.as_ref(), agent_identity.key().as_ref()], bump = wallet_membership.bump, constraint = wallet_membership.agent_id == agent_identity.key() @ SaidError::WalletNotLinked)]
+    pub wallet_membership: Account<'info, WalletMembership>,
+    #[account(mut, constraint = caller.key() == agent_identity.authority || caller.key() == wallet_membership.wallet @ SaidError::Unauthorized)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(evm_address: [u8; 20])]
+pub struct LinkEvmWallet<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(init, payer = authority, space = 8 + EvmWalletLink::INIT_SPACE, seeds = [b"evm_wallet", evm_address.as_ref()], bump)]
+    pub evm_wallet_link: Account<'info, EvmWalletLink>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnlinkEvmWallet<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(mut, close = authority, seeds = [b"evm_wallet", evm_wallet_link.evm_address.as_ref()], bump = evm_wallet_link.bump, constraint = evm_wallet_link.agent_id == agent_identity.key() @ SaidError::WalletNotLinked)]
+    pub evm_wallet_link: Account<'info, EvmWalletLink>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferAuthority<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(seeds = [b"wallet", new_authority.key().as_ref()], bump = wallet_link.bump, constraint = wallet_link.agent_id == agent_identity.key() @ SaidError::WalletNotLinked)]
+    pub wallet_link: Account<'info, WalletLink>,
+    #[account(seeds = [b"policy", agent_identity.key().as_ref()], bump = authority_policy.bump)]
+    pub authority_policy: Option<Account<'info, AuthorityPolicy>>,
+    #[account(mut, seeds = [b"proposal", agent_identity.key().as_ref(), &policy_proposal.index.to_le_bytes()], bump = policy_proposal.bump)]
+    pub policy_proposal: Option<Account<'info, AuthorityProposal>>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAuthorityPolicy<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(init_if_needed, payer = authority, space = 8 + AuthorityPolicy::INIT_SPACE, seeds = [b"policy", agent_identity.key().as_ref()], bump)]
+    pub authority_policy: Account<'info, AuthorityPolicy>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposePolicyAction<'info> {
+    #[account(mut, seeds = [b"policy", authority_policy.agent_id.as_ref()], bump = authority_policy.bump, constraint = authority_policy.members.contains(&proposer.key()) @ SaidError::NotPolicyMember)]
+    pub authority_policy: Account<'info, AuthorityPolicy>,
+    #[account(init, payer = proposer, space = 8 + AuthorityProposal::INIT_SPACE, seeds = [b"proposal", authority_policy.agent_id.as_ref(), &authority_policy.proposal_count.to_le_bytes()], bump)]
+    pub policy_proposal: Account<'info, AuthorityProposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApprovePolicyAction<'info> {
+    #[account(seeds = [b"policy", authority_policy.agent_id.as_ref()], bump = authority_policy.bump, constraint = authority_policy.members.contains(&member.key()) @ SaidError::NotPolicyMember)]
+    pub authority_policy: Account<'info, AuthorityPolicy>,
+    #[account(mut, seeds = [b"proposal", authority_policy.agent_id.as_ref(), &policy_proposal.index.to_le_bytes()], bump = policy_proposal.bump)]
+    pub policy_proposal: Account<'info, AuthorityProposal>,
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePolicyProposal<'info> {
+    #[account(seeds = [b"policy", policy_proposal.agent_id.as_ref()], bump = authority_policy.bump)]
+    pub authority_policy: Account<'info, AuthorityPolicy>,
+    #[account(mut, close = proposer, has_one = proposer @ SaidError::Unauthorized, seeds = [b"proposal", authority_policy.agent_id.as_ref(), &policy_proposal.index.to_le_bytes()], bump = policy_proposal.bump)]
+    pub policy_proposal: Account<'info, AuthorityProposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAuthorityPolicy<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = agent_identity.has_authority_policy @ SaidError::NoAuthorityPolicy)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(mut, seeds = [b"policy", agent_identity.key().as_ref()], bump = authority_policy.bump, constraint = authority_policy.members.contains(&member.key()) @ SaidError::NotPolicyMember)]
+    pub authority_policy: Account<'info, AuthorityPolicy>,
+    #[account(mut, seeds = [b"proposal", agent_identity.key().as_ref(), &policy_proposal.index.to_le_bytes()], bump = policy_proposal.bump)]
+    pub policy_proposal: Account<'info, AuthorityProposal>,
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveAuthorityPolicy<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = agent_identity.has_authority_policy @ SaidError::NoAuthorityPolicy)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(mut, seeds = [b"policy", agent_identity.key().as_ref()], bump = authority_policy.bump, constraint = authority_policy.members.contains(&member.key()) @ SaidError::NotPolicyMember)]
+    pub authority_policy: Account<'info, AuthorityPolicy>,
+    #[account(mut, seeds = [b"proposal", agent_identity.key().as_ref(), &policy_proposal.index.to_le_bytes()], bump = policy_proposal.bump)]
+    pub policy_proposal: Account<'info, AuthorityProposal>,
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(metadata_uri: String)]
+pub struct SponsorRegister<'info> {
+    #[account(init, payer = authority, space = 8 + AgentIdentity::INIT_SPACE, seeds = [b"agent", agent_wallet.key().as_ref()], bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    /// CHECK: PDA seed only (not a signer)
+    pub agent_wallet: UncheckedAccount<'info>,
+    // required unless the signer is the treasury authority
+    #[account(mut, seeds = [b"sponsor", authority.key().as_ref()], bump = sponsor.bump)]
+    pub sponsor: Option<Account<'info, Sponsor>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SponsorVerify<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    // required unless the signer is the treasury authority
+    #[account(mut, seeds = [b"sponsor", authority.key().as_ref()], bump = sponsor.bump)]
+    pub sponsor: Option<Account<'info, Sponsor>>,
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptSponsoredIdentity<'info> {
+    #[account(mut, seeds = [b"agent", agent_wallet.key().as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    pub agent_wallet: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RejectSponsoredIdentity<'info> {
+    #[account(mut, close = sponsor_wallet, seeds = [b"agent", agent_wallet.key().as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    pub agent_wallet: Signer<'info>,
+    /// CHECK: rent goes back to whoever sponsored the registration
+    #[account(mut, constraint = agent_identity.sponsor == Some(sponsor_wallet.key()) @ SaidError::NotApprovedSponsor)]
+    pub sponsor_wallet: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveSponsor<'info> {
+    #[account(init, payer = admin, space = 8 + Sponsor::INIT_SPACE, seeds = [b"sponsor", sponsor_wallet.key().as_ref()], bump)]
+    pub sponsor: Account<'info, Sponsor>,
+    /// CHECK: PDA seed only (the partner's signing wallet)
+    pub sponsor_wallet: UncheckedAccount<'info>,
+    #[account(mut, address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSponsor<'info> {
+    #[account(mut, seeds = [b"sponsor", sponsor.wallet.as_ref()], bump = sponsor.bump)]
+    pub sponsor: Account<'info, Sponsor>,
+    #[account(address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSponsor<'info> {
+    #[account(mut, close = admin, seeds = [b"sponsor", sponsor.wallet.as_ref()], bump = sponsor.bump)]
+    pub sponsor: Account<'info, Sponsor>,
+    #[account(mut, address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterAttester<'info> {
+    #[account(init, payer = admin, space = 8 + Attester::INIT_SPACE, seeds = [b"attester", attester_wallet.key().as_ref()], bump)]
+    pub attester: Account<'info, Attester>,
+    /// CHECK: PDA seed only (the attester's signing wallet)
+    pub attester_wallet: UncheckedAccount<'info>,
+    #[account(mut, address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAttester<'info> {
+    #[account(mut, seeds = [b"attester", attester.wallet.as_ref()], bump = attester.bump)]
+    pub attester: Account<'info, Attester>,
+    #[account(address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeAttester<'info> {
+    #[account(mut, close = admin, seeds = [b"attester", attester.wallet.as_ref()], bump = attester.bump)]
+    pub attester: Account<'info, Attester>,
+    #[account(mut, address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: [u8; 32])]
+pub struct RegisterSchema<'info> {
+    #[account(init, payer = creator, space = 8 + Schema::INIT_SPACE, seeds = [b"schema", id.as_ref()], bump)]
+    pub schema: Account<'info, Schema>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(schema_id: [u8; 32])]
+pub struct IssueAttestation<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(seeds = [b"attester", authority.key().as_ref()], bump = attester.bump)]
+    pub attester: Account<'info, Attester>,
+    #[account(seeds = [b"schema", schema_id.as_ref()], bump = schema.bump)]
+    pub schema: Account<'info, Schema>,
+    #[account(init_if_needed, payer = authority, space = 8 + Attestation::INIT_SPACE, seeds = [b"attestation", agent_identity.key().as_ref(), authority.key().as_ref(), schema_id.as_ref()], bump)]
+    pub attestation: Account<'info, Attestation>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAttestation<'info> {
+    #[account(seeds = [b"attester", authority.key().as_ref()], bump = attester.bump)]
+    pub attester: Account<'info, Attester>,
+    #[account(mut, seeds = [b"attestation", attestation.agent_id.as_ref(), authority.key().as_ref(), attestation.schema_id.as_ref()], bump = attestation.bump)]
+    pub attestation: Account<'info, Attestation>,
+    #[account(seeds = [b"schema", attestation.schema_id.as_ref()], bump = schema.bump)]
+    pub schema: Account<'info, Schema>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeAttestation<'info> {
+    #[account(mut, seeds = [b"attestation", attestation.agent_id.as_ref(), authority.key().as_ref(), attestation.schema_id.as_ref()], bump = attestation.bump)]
+    pub attestation: Account<'info, Attestation>,
+    #[account(seeds = [b"schema", attestation.schema_id.as_ref()], bump = schema.bump)]
+    pub schema: Account<'info, Schema>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitFeedback<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(init_if_needed, payer = reviewer, space = 8 + AgentReputation::INIT_SPACE, seeds = [b"reputation", agent_identity.key().as_ref()], bump)]
+    pub agent_reputation: Account<'info, AgentReputation>,
+    #[account(init_if_needed, payer = reviewer, space = 8 + FeedbackRecord::INIT_SPACE, seeds = [b"feedback", agent_identity.key().as_ref(), reviewer.key().as_ref()], bump)]
+    pub feedback_record: Account<'info, FeedbackRecord>,
+    // reviewer's own SAID standing, if any; weighs the feedback
+    #[account(seeds = [b"agent", reviewer.key().as_ref()], bump = reviewer_identity.bump)]
+    pub reviewer_identity: Option<Account<'info, AgentIdentity>>,
+    pub reviewer_stake: Option<Account<'info, AgentStake>>,
+    /// CHECK: ValidationRecord or ReceiptAnchor named by `proof`; owner and contents checked in verify_interaction
+    pub interaction_evidence: Option<UncheckedAccount<'info>>,
+    // category the verdict is filed under (fixed once the record exists), with the agent's bucket for it
+    #[account(seeds = [b"category".as_ref(), &category.id.to_le_bytes()], bump = category.bump)]
+    pub category: Option<Account<'info, Category>>,
+    #[account(mut, seeds = [b"category_reputation", agent_identity.key().as_ref(), &category.as_ref().map_or(0, |c| c.id).to_le_bytes()], bump = category_reputation.bump)]
+    pub category_reputation: Option<Account<'info, AgentReputation>>,
+    /// CHECK: config PDA; default reputation prior applies while it is uninitialized (see reputation_prior)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub reviewer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenFeedbackDispute<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(seeds = [b"feedback", agent_identity.key().as_ref(), feedback_record.reviewer.as_ref()], bump = feedback_record.bump)]
+    pub feedback_record: Account<'info, FeedbackRecord>,
+    #[account(init_if_needed, payer = authority, space = 8 + FeedbackDispute::INIT_SPACE, seeds = [b"dispute", feedback_record.key().as_ref()], bump)]
+    pub feedback_dispute: Account<'info, FeedbackDispute>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveFeedbackDispute<'info> {
+    #[account(mut, seeds = [b"dispute", feedback_record.key().as_ref()], bump = feedback_dispute.bump)]
+    pub feedback_dispute: Account<'info, FeedbackDispute>,
+    #[account(mut, seeds = [b"feedback", feedback_record.agent_id.as_ref(), feedback_record.reviewer.as_ref()], bump = feedback_record.bump)]
+    pub feedback_record: Account<'info, FeedbackRecord>,
+    #[account(mut, seeds = [b"reputation", feedback_record.agent_id.as_ref()], bump = agent_reputation.bump)]
+    pub agent_reputation: Account<'info, AgentReputation>,
+    // required when the record is filed under a category
+    #[account(mut, seeds = [b"category_reputation", feedback_record.agent_id.as_ref(), &feedback_record.category.unwrap_or_default().to_le_bytes()], bump = category_reputation.bump)]
+    pub category_reputation: Option<Account<'info, AgentReputation>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
+    pub resolver: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetReputation<'info> {
+    #[account(seeds = [b"reputation", agent_reputation.agent_id.as_ref()], bump = agent_reputation.bump)]
+    pub agent_reputation: Account<'info, AgentReputation>,
+    /// CHECK: config PDA; default reputation prior applies while it is uninitialized (see reputation_prior)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SnapshotReputation<'info> {
+    #[account(seeds = [b"reputation", agent_reputation.agent_id.as_ref()], bump = agent_reputation.bump)]
+    pub agent_reputation: Account<'info, AgentReputation>,
+    #[account(init_if_needed, payer = payer, space = 8 + ReputationHistory::INIT_SPACE, seeds = [b"reputation_history", agent_reputation.agent_id.as_ref()], bump)]
+    pub reputation_history: Account<'info, ReputationHistory>,
+    /// CHECK: config PDA; default reputation prior applies while it is uninitialized (see reputation_prior)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetReputationSnapshot<'info> {
+    #[account(seeds = [b"reputation_history", reputation_history.agent_id.as_ref()], bump = reputation_history.bump)]
+    pub reputation_history: Account<'info, ReputationHistory>,
+}
+
+#[derive(Accounts)]
+pub struct GetCategoryReputation<'info> {
+    #[account(seeds = [b"category".as_ref(), &category.id.to_le_bytes()], bump = category.bump)]
+    pub category: Account<'info, Category>,
+    #[account(seeds = [b"category_reputation", category_reputation.agent_id.as_ref(), &category.id.to_le_bytes()], bump = category_reputation.bump)]
+    pub category_reputation: Account<'info, AgentReputation>,
+    /// CHECK: config PDA; default reputation prior applies while it is uninitialized (see reputation_prior)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u16)]
+pub struct RegisterCategory<'info> {
+    #[account(init, payer = admin, space = 8 + Category::INIT_SPACE, seeds = [b"category".as_ref(), &id.to_le_bytes()], bump)]
+    pub category: Account<'info, Category>,
+    #[account(mut, address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCategory<'info> {
+    #[account(mut, seeds = [b"category".as_ref(), &category.id.to_le_bytes()], bump = category.bump)]
+    pub category: Account<'info, Category>,
+    #[account(address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenCategoryReputation<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
     pub agent_identity: Account<'info, AgentIdentity>,
-    #[account(init, payer = owner, space = 8 + AgentStake::INIT_SPACE, seeds = [b"stake", agent_identity.key().as_ref()], bump)]
-    pub agent_stake: Account<'info, AgentStake>,
-    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
-    pub treasury: Account<'info, Treasury>,
+    #[account(seeds = [b"category".as_ref(), &category.id.to_le_bytes()], bump = category.bump)]
+    pub category: Account<'info, Category>,
+    #[account(init, payer = payer, space = 8 + AgentReputation::INIT_SPACE, seeds = [b"category_reputation", agent_identity.key().as_ref(), &category.id.to_le_bytes()], bump)]
+    pub category_reputation: Account<'info, AgentReputation>,
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct GetVerified<'info> {
+pub struct SetFeedbackPolicy<'info> {
     #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
     pub agent_identity: Account<'info, AgentIdentity>,
-    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
-    pub treasury: Account<'info, Treasury>,
-    #[account(mut)]
     pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateAgent<'info> {
-    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
-    pub agent_identity: Account<'info, AgentIdentity>,
-    pub authority: Signer<'info>,
+pub struct WithdrawFeedback<'info> {
+    #[account(mut, seeds = [b"reputation", feedback_record.agent_id.as_ref()], bump = agent_reputation.bump)]
+    pub agent_reputation: Account<'info, AgentReputation>,
+    #[account(mut, close = reviewer, seeds = [b"feedback", feedback_record.agent_id.as_ref(), reviewer.key().as_ref()], bump = feedback_record.bump)]
+    pub feedback_record: Account<'info, FeedbackRecord>,
+    // required when the record carries ratings
+    #[account(mut, seeds = [b"ratings", feedback_record.agent_id.as_ref()], bump = agent_ratings.bump)]
+    pub agent_ratings: Option<Account<'info, AgentRatings>>,
+    // required when the record is filed under a category
+    #[account(mut, seeds = [b"category_reputation", feedback_record.agent_id.as_ref(), &feedback_record.category.unwrap_or_default().to_le_bytes()], bump = category_reputation.bump)]
+    pub category_reputation: Option<Account<'info, AgentReputation>>,
+    /// CHECK: config PDA; default reputation prior applies while it is uninitialized (see reputation_prior)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: dispute PDA for this record; must be uninitialized or a rejected FeedbackDispute (checked in withdraw_feedback)
+    #[account(seeds = [b"dispute", feedback_record.key().as_ref()], bump)]
+    pub feedback_dispute: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub reviewer: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct LinkWallet<'info> {
-    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
-    pub agent_identity: Account<'info, AgentIdentity>,
-    #[account(init, payer = authority, space = 8 + WalletLink::INIT_SPACE, seeds = [b"wallet", new_wallet.key().as_ref()], bump)]
-    pub wallet_link: Account<'info, WalletLink>,
+pub struct SubmitRating<'info> {
+    pub feedback: SubmitFeedback<'info>,
+    #[account(init_if_needed, payer = payer, space = 8 + AgentRatings::INIT_SPACE, seeds = [b"ratings", feedback.agent_identity.key().as_ref()], bump)]
+    pub agent_ratings: Account<'info, AgentRatings>,
     #[account(mut)]
-    pub authority: Signer<'info>,
-    pub new_wallet: Signer<'info>,
+    pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct UnlinkWallet<'info> {
+#[instruction(task_hash: [u8; 32])]
+pub struct ValidateWork<'info> {
     #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
     pub agent_identity: Account<'info, AgentIdentity>,
-    #[account(mut, close = caller, seeds = [b"wallet", wallet_link.wallet.as_ref()], bump = wallet_link.bump, constraint = wallet_link.agent_id == agent_identity.key() @ SaidError::WalletNotLinked)]
-    pub wallet_link: Account<'info, WalletLink>,
-    #[account(mut, constraint = caller.key() == agent_identity.authority || caller.key() == wallet_link.wallet @ SaidError::Unauthorized)]
-    pub caller: Signer<'info>,
+    #[account(init, payer = validator, space = 8 + ValidationRecord::INIT_SPACE, seeds = [b"validation", agent_identity.key().as_ref(), task_hash.as_ref()], bump)]
+    pub validation_record: Account<'info, ValidationRecord>,
+    #[account(mut)]
+    pub validator: Signer<'info>,
+    // agent authority co-signing the task; only co-signed records count as InteractionProof::Validation
+    #[account(address = agent_identity.authority @ SaidError::Unauthorized)]
+    pub agent_authority: Option<Signer<'info>>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct TransferAuthority<'info> {
-    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+pub struct ComputeTrustScore<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
     pub agent_identity: Account<'info, AgentIdentity>,
-    #[account(seeds = [b"wallet", new_authority.key().as_ref()], bump = wallet_link.bump, constraint = wallet_link.agent_id == agent_identity.key() @ SaidError::WalletNotLinked)]
-    pub wallet_link: Account<'info, WalletLink>,
-    pub new_authority: Signer<'info>,
+    #[account(seeds = [b"stake", agent_identity.key().as_ref()], bump = agent_stake.bump)]
+    pub agent_stake: Option<Account<'info, AgentStake>>,
+    #[account(seeds = [b"reputation", agent_identity.key().as_ref()], bump = agent_reputation.bump)]
+    pub agent_reputation: Option<Account<'info, AgentReputation>>,
+    /// CHECK: ExternalReputation PDA for the agent and the config's reputation_oracle, required once one is
+    /// pinned; may be uninitialized (checked in compute_trust_score)
+    pub external_reputation: Option<UncheckedAccount<'info>>,
+    /// CHECK: config PDA; no oracle counts while it is uninitialized (see reputation_oracle)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(metadata_uri: String)]
-pub struct SponsorRegister<'info> {
-    #[account(init, payer = authority, space = 8 + AgentIdentity::INIT_SPACE, seeds = [b"agent", agent_wallet.key().as_ref()], bump)]
-    pub agent_identity: Account<'info, AgentIdentity>,
-    /// CHECK: PDA seed only (not a signer)
-    pub agent_wallet: UncheckedAccount<'info>,
+pub struct RegisterOracle<'info> {
+    #[account(init, payer = admin, space = 8 + Oracle::INIT_SPACE, seeds = [b"oracle", oracle_signer.key().as_ref()], bump)]
+    pub oracle: Account<'info, Oracle>,
+    /// CHECK: PDA seed only (the oracle's ed25519 signing key)
+    pub oracle_signer: UncheckedAccount<'info>,
     #[account(mut, address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
-    pub authority: Signer<'info>,
+    pub admin: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct SponsorVerify<'info> {
-    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
-    pub agent_identity: Account<'info, AgentIdentity>,
+pub struct UpdateOracle<'info> {
+    #[account(mut, seeds = [b"oracle", oracle.signer.as_ref()], bump = oracle.bump)]
+    pub oracle: Account<'info, Oracle>,
     #[account(address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
-    pub authority: Signer<'info>,
+    pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct SubmitFeedback<'info> {
+pub struct UpdateExternalReputation<'info> {
     #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
     pub agent_identity: Account<'info, AgentIdentity>,
-    #[account(init_if_needed, payer = reviewer, space = 8 + AgentReputation::INIT_SPACE, seeds = [b"reputation", agent_identity.key().as_ref()], bump)]
-    pub agent_reputation: Account<'info, AgentReputation>,
+    #[account(seeds = [b"oracle", oracle.signer.as_ref()], bump = oracle.bump)]
+    pub oracle: Account<'info, Oracle>,
+    #[account(init_if_needed, payer = payer, space = 8 + ExternalReputation::INIT_SPACE, seeds = [b"external_reputation", agent_identity.key().as_ref(), oracle.signer.as_ref()], bump)]
+    pub external_reputation: Account<'info, ExternalReputation>,
+    /// CHECK: instructions sysvar
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
     #[account(mut)]
-    pub reviewer: Signer<'info>,
+    pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(task_hash: [u8; 32])]
-pub struct ValidateWork<'info> {
-    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+pub struct Stake<'info> {
+    #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
     pub agent_identity: Account<'info, AgentIdentity>,
-    #[account(init, payer = validator, space = 8 + ValidationRecord::INIT_SPACE, seeds = [b"validation", agent_identity.key().as_ref(), task_hash.as_ref()], bump)]
-    pub validation_record: Account<'info, ValidationRecord>,
+    // kept (empty) after a full unstake, so re-staking reuses it
+    #[account(init_if_needed, payer = authority, space = 8 + AgentStake::INIT_SPACE, seeds = [b"stake", agent_identity.key().as_ref()], bump)]
+    pub agent_stake: Account<'info, AgentStake>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
     #[account(mut)]
-    pub validator: Signer<'info>,
+    pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Stake<'info> {
+pub struct VerifyByStake<'info> {
     #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump, constraint = authority.key() == agent_identity.authority @ SaidError::Unauthorized)]
     pub agent_identity: Account<'info, AgentIdentity>,
-    #[account(init, payer = authority, space = 8 + AgentStake::INIT_SPACE, seeds = [b"stake", agent_identity.key().as_ref()], bump)]
+    #[account(init_if_needed, payer = authority, space = 8 + AgentStake::INIT_SPACE, seeds = [b"stake", agent_identity.key().as_ref()], bump)]
     pub agent_stake: Account<'info, AgentStake>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
     #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -599,6 +2895,8 @@ pub struct AddStake<'info> {
     pub agent_identity: Account<'info, AgentIdentity>,
     #[account(mut, seeds = [b"stake", agent_identity.key().as_ref()], bump = agent_stake.bump, constraint = agent_stake.agent_id == agent_identity.key())]
     pub agent_stake: Account<'info, AgentStake>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
     #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -630,6 +2928,15 @@ pub struct CompleteUnstake<'info> {
     pub agent_identity: Account<'info, AgentIdentity>,
     #[account(mut, seeds = [b"stake", agent_identity.key().as_ref()], bump = agent_stake.bump, constraint = agent_stake.agent_id == agent_identity.key())]
     pub agent_stake: Account<'info, AgentStake>,
+    /// CHECK: payout destination, must be the stake's recorded withdrawal address
+    #[account(mut, address = agent_stake.withdrawal_address @ SaidError::InvalidWithdrawalAddress)]
+    pub withdrawal_address: UncheckedAccount<'info>,
+    #[account(seeds = [b"policy", agent_identity.key().as_ref()], bump = authority_policy.bump)]
+    pub authority_policy: Option<Account<'info, AuthorityPolicy>>,
+    #[account(mut, seeds = [b"proposal", agent_identity.key().as_ref(), &policy_proposal.index.to_le_bytes()], bump = policy_proposal.bump)]
+    pub policy_proposal: Option<Account<'info, AuthorityProposal>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
     #[account(mut)]
     pub authority: Signer<'info>,
 }
@@ -640,12 +2947,53 @@ pub struct EmergencyUnstake<'info> {
     pub agent_identity: Account<'info, AgentIdentity>,
     #[account(mut, seeds = [b"stake", agent_identity.key().as_ref()], bump = agent_stake.bump, constraint = agent_stake.agent_id == agent_identity.key())]
     pub agent_stake: Account<'info, AgentStake>,
+    /// CHECK: payout destination, must be the stake's recorded withdrawal address
+    #[account(mut, address = agent_stake.withdrawal_address @ SaidError::InvalidWithdrawalAddress)]
+    pub withdrawal_address: UncheckedAccount<'info>,
+    #[account(seeds = [b"policy", agent_identity.key().as_ref()], bump = authority_policy.bump)]
+    pub authority_policy: Option<Account<'info, AuthorityPolicy>>,
+    #[account(mut, seeds = [b"proposal", agent_identity.key().as_ref(), &policy_proposal.index.to_le_bytes()], bump = policy_proposal.bump)]
+    pub policy_proposal: Option<Account<'info, AuthorityProposal>>,
     #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
     pub treasury: Account<'info, Treasury>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
     #[account(mut)]
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct RequestWithdrawalAddressChange<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(mut, seeds = [b"stake", agent_identity.key().as_ref()], bump = agent_stake.bump, constraint = agent_stake.agent_id == agent_identity.key())]
+    pub agent_stake: Account<'info, AgentStake>,
+    #[account(seeds = [b"policy", agent_identity.key().as_ref()], bump = authority_policy.bump)]
+    pub authority_policy: Option<Account<'info, AuthorityPolicy>>,
+    #[account(mut, seeds = [b"proposal", agent_identity.key().as_ref(), &policy_proposal.index.to_le_bytes()], bump = policy_proposal.bump)]
+    pub policy_proposal: Option<Account<'info, AuthorityProposal>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteWithdrawalAddressChange<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(mut, seeds = [b"stake", agent_identity.key().as_ref()], bump = agent_stake.bump, constraint = agent_stake.agent_id == agent_identity.key())]
+    pub agent_stake: Account<'info, AgentStake>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelWithdrawalAddressChange<'info> {
+    #[account(seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
+    pub agent_identity: Account<'info, AgentIdentity>,
+    #[account(mut, seeds = [b"stake", agent_identity.key().as_ref()], bump = agent_stake.bump, constraint = agent_stake.agent_id == agent_identity.key())]
+    pub agent_stake: Account<'info, AgentStake>,
+    #[account(constraint = caller.key() == agent_identity.authority || caller.key() == agent_stake.withdrawal_address @ SaidError::Unauthorized)]
+    pub caller: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct SlashAgent<'info> {
     #[account(mut, seeds = [b"agent", agent_identity.owner.as_ref()], bump = agent_identity.bump)]
@@ -654,6 +3002,8 @@ pub struct SlashAgent<'info> {
     pub agent_stake: Account<'info, AgentStake>,
     #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
     pub treasury: Account<'info, Treasury>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProtocolConfig>,
     #[account(mut, address = TREASURY_AUTHORITY @ SaidError::UnauthorizedAuthority)]
     pub admin: Signer<'info>,
 }
@@ -676,6 +3026,86 @@ pub struct SubmitAnchor<'info> {
 #[derive(InitSpace)]
 pub struct Treasury { pub authority: Pubkey, pub total_collected: u64, pub bump: u8 }
 
+/// Prior for `confidence_score`: `weight` (feedback_weight units) of reviews averaging `score_bps`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReputationPrior {
+    pub score_bps: u16,
+    pub weight: u32,
+}
+
+/// Return value of `get_reputation`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReputationView {
+    pub score: u16,          // bps, positive share of decayed weight
+    pub decayed_score: u16,  // bps, see decayed_score()
+    pub confidence_score: u16, // bps, Bayesian average of the decayed weights
+    pub decayed_positive: u64,
+    pub decayed_negative: u64,
+}
+
+/// Return value of `compute_trust_score`, in hundredths of a point
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrustScore {
+    pub total: u16,        // 0-10_000
+    pub verification: u16, // 0-2_500
+    pub stake: u16,        // 0-2_500
+    pub reputation: u16,   // 0-5_000
+    pub oracle_fallback: bool, // no fresh oracle score: blended with NEUTRAL_EXTERNAL_SCORE_BPS (or a lower stale score)
+}
+
+/// Admin-tunable protocol parameters
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolConfig {
+    // minimum stake (lamports) for each staked tier
+    pub secured_threshold: u64,
+    pub professional_threshold: u64,
+    pub enterprise_threshold: u64,
+    // stake that verifies an agent without the fee (verify_by_stake)
+    pub stake_verification_threshold: u64,
+    // applied to VERIFICATION_FEE for agents with slash or revocation history
+    pub reverification_fee_multiplier_bps: u16,
+    // resolves feedback disputes alongside the treasury authority
+    pub arbiter: Option<Pubkey>,
+    // oracle signing key whose scores count in compute_trust_score
+    pub reputation_oracle: Option<Pubkey>,
+    // Bayesian prior behind AgentReputation.confidence_score
+    pub reputation_prior_score_bps: u16,
+    pub reputation_prior_weight: u32,
+    // schema id build attestations must use (attach_build_attestation)
+    pub build_schema: Option<[u8; 32]>,
+    pub bump: u8,
+}
+
+/// How an identity obtained its current verification
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum VerificationSource {
+    Paid,
+    Sponsored,
+    Stake,
+}
+
+/// Reason code carried by `revoke_verification` and `VerificationRevoked`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RevocationReason {
+    Slashed,
+    Fraud,
+    Impersonation,
+    CompromisedKeys,
+    SponsorRevoked,
+    Other,
+}
+
+/// Trust tiers from SLASHING-SPEC-V2, in ascending order
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, InitSpace)]
+pub enum VerificationTier {
+    Registered,
+    Verified,
+    Secured,
+    Professional,
+    Enterprise,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct AgentIdentity {
@@ -685,7 +3115,7 @@ pub struct AgentIdentity {
     pub created_at: i64,
     pub is_verified: bool,
     pub verified_at: Option<i64>,
-    pub verification_tier: u8,
+    pub verification_tier: VerificationTier,
     pub stake_amount: u64,
     pub staked_at: Option<i64>,
     pub slash_count: u32,
@@ -693,6 +3123,61 @@ pub struct AgentIdentity {
     // receipts/anchors continuity
     pub last_receipt_seq: u64,
     pub last_anchor_index: u64,
+    // replay protection for signature-based wallet links
+    pub link_nonce: u64,
+    pub has_authority_policy: bool,
+    // who created this identity via sponsor_register, if anyone
+    pub sponsor: Option<Pubkey>,
+    // sponsored identities stay unclaimed until the agent wallet accepts them
+    pub pending_claim: bool,
+    // None = verification without expiry (identities verified before expiry existed)
+    pub verified_until: Option<i64>,
+    // how the current verification was obtained; None = unverified or verified before this was recorded
+    pub verification_source: Option<VerificationSource>,
+    // admin revocations and slash-outs; any history raises the re-verification fee
+    pub verification_revocations: u32,
+    // number of AgentBuild records; the latest is version build_count - 1
+    pub build_count: u32,
+    // only accept feedback that proves an interaction (ValidationRecord or anchored receipt)
+    pub require_interaction_proof: bool,
+    pub bump: u8,
+}
+
+/// Operations that need multisig approval once an identity has an `AuthorityPolicy`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum PolicyAction {
+    UpdateAgent { #[max_len(200)] new_metadata_uri: String },
+    TransferAuthority { new_authority: Pubkey },
+    CompleteUnstake,
+    EmergencyUnstake,
+    UpdatePolicy { threshold: u8, #[max_len(10)] members: Vec<Pubkey> },
+    RemovePolicy,
+    ChangeWithdrawalAddress { new_address: Pubkey },
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AuthorityPolicy {
+    pub agent_id: Pubkey,
+    pub threshold: u8,
+    #[max_len(10)] pub members: Vec<Pubkey>,
+    // bumped on every policy change; proposals from older versions can't execute
+    pub version: u32,
+    pub proposal_count: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AuthorityProposal {
+    pub agent_id: Pubkey,
+    pub index: u64,
+    pub policy_version: u32,
+    pub action: PolicyAction,
+    pub proposer: Pubkey,
+    #[max_len(10)] pub approvals: Vec<Pubkey>,
+    pub created_at: i64,
+    pub executed: bool,
     pub bump: u8,
 }
 
@@ -700,17 +3185,268 @@ pub struct AgentIdentity {
 #[derive(InitSpace)]
 pub struct WalletLink { pub agent_id: Pubkey, pub wallet: Pubkey, pub bump: u8 }
 
+/// Many-to-many membership for shared wallets; never used for authority recovery
+#[account]
+#[derive(InitSpace)]
+pub struct WalletMembership { pub agent_id: Pubkey, pub wallet: Pubkey, pub joined_at: i64, pub bump: u8 }
+
+#[account]
+#[derive(InitSpace)]
+pub struct EvmWalletLink { pub agent_id: Pubkey, pub evm_address: [u8; 20], pub linked_at: i64, pub bump: u8 }
+
+/// Partner platform approved by the treasury authority to onboard agents
+#[account]
+#[derive(InitSpace)]
+pub struct Sponsor {
+    pub wallet: Pubkey,
+    #[max_len(32)] pub label: String,
+    pub registration_quota: u32,
+    pub registrations_used: u32,
+    pub verification_quota: u32,
+    pub verifications_used: u32,
+    pub expires_at: i64,
+    pub approved_at: i64,
+    pub bump: u8,
+}
+
+/// Declared model / framework / code build of an agent, one per version
+#[account]
+#[derive(InitSpace)]
+pub struct AgentBuild {
+    pub agent_id: Pubkey,
+    pub version: u32,
+    #[max_len(32)] pub framework: String,
+    #[max_len(64)] pub model: String,
+    // code or container image hash
+    pub code_hash: [u8; 32],
+    #[max_len(200)] pub build_uri: Option<String>,
+    // Attestation PDA confirming this build, if any
+    pub attestation: Option<Pubkey>,
+    pub declared_at: i64,
+    pub bump: u8,
+}
+
+/// Third party (KYC provider, audit firm, platform) approved by the treasury authority
+#[account]
+#[derive(InitSpace)]
+pub struct Attester {
+    pub wallet: Pubkey,
+    #[max_len(32)] pub name: String,
+    // schema ids this attester may issue
+    #[max_len(8)] pub schemas: Vec<[u8; 32]>,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+/// Shared layout for attestations; `descriptor` is a Borsh/JSON field description
+#[account]
+#[derive(InitSpace)]
+pub struct Schema {
+    pub id: [u8; 32],
+    pub creator: Pubkey,
+    #[max_len(256)] pub descriptor: String,
+    pub field_count: u8,
+    // max total bytes across all fields
+    pub data_size: u16,
+    pub revocable: bool,
+    pub resolver: ResolverPolicy,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+/// Who may issue attestations for a schema, on top of the attester allowlist
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum ResolverPolicy {
+    AllowedAttesters,
+    CreatorOnly,
+}
+
+/// One claim per (agent, attester, schema); the payload lives off-chain at `uri`
+#[account]
+#[derive(InitSpace)]
+pub struct Attestation {
+    pub agent_id: Pubkey,
+    pub attester: Pubkey,
+    pub schema_id: [u8; 32],
+    pub data_hash: [u8; 32],
+    #[max_len(200)] pub uri: String,
+    pub issued_at: i64,
+    pub updated_at: i64,
+    pub expires_at: Option<i64>,
+    pub revoked: bool,
+    pub revoked_at: Option<i64>,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AgentReputation {
+    pub agent_id: Pubkey,
+    pub total_interactions: u64,
+    pub positive_feedback: u64,
+    pub negative_feedback: u64,
+    // lifetime totals of feedback_weight
+    pub weighted_positive: u64,
+    pub weighted_negative: u64,
+    // the same weights (x REPUTATION_DECAY_SCALE) decayed to last_decay_ts; reputation_score uses these
+    pub decayed_positive: u64,
+    pub decayed_negative: u64,
+    pub last_decay_ts: i64,
+    pub reputation_score: u16,
+    // Bayesian average of the decayed weights against the config prior; prefer this for small samples
+    pub confidence_score: u16,
+    pub last_updated: i64,
+    pub bump: u8,
+}
+
+/// Bounded reputation history for charts and point-in-time proofs, filled by snapshot_reputation
+#[account]
+#[derive(InitSpace)]
+pub struct ReputationHistory {
+    pub agent_id: Pubkey,
+    // ring buffer: `head` is the next slot written, the newest entry sits just before it
+    pub snapshots: [ReputationSnapshot; REPUTATION_HISTORY_LEN],
+    pub head: u16,
+    pub count: u16,
+    pub last_snapshot_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct ReputationSnapshot {
+    pub timestamp: i64,
+    pub score: u16, // bps, decayed to `timestamp`
+    pub confidence_score: u16,
+    pub total_interactions: u64,
+    pub positive_feedback: u64,
+    pub negative_feedback: u64,
+}
+
+/// Registered external reputation source, keyed by the ed25519 key it signs scores with
+#[account]
+#[derive(InitSpace)]
+pub struct Oracle {
+    pub signer: Pubkey,
+    #[max_len(32)] pub name: String,
+    pub active: bool,
+    // scores older than this are rejected on import and ignored by trust_score
+    pub max_staleness_secs: i64,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+/// Latest oracle-signed score for an agent (one per agent + oracle)
+#[account]
+#[derive(InitSpace)]
+pub struct ExternalReputation {
+    pub agent_id: Pubkey,
+    pub oracle: Pubkey,
+    pub score_bps: u16,
+    // oracle's own timestamp for the score, and when it stops counting
+    pub observed_at: i64,
+    pub stale_after: i64,
+    // last accepted nonce; updates must use a higher one
+    pub nonce: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+/// Admin-managed feedback category; an agent's reputation within it is an AgentReputation
+/// at [b"category_reputation", agent, id]
+#[account]
+#[derive(InitSpace)]
+pub struct Category {
+    pub id: u16,
+    #[max_len(32)] pub name: String,
+    pub active: bool,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+/// Agent's response to one feedback record, resolved by the admin or arbiter
+#[account]
+#[derive(InitSpace)]
+pub struct FeedbackDispute {
+    pub agent_id: Pubkey,
+    pub reviewer: Pubkey,
+    pub feedback_record: Pubkey,
+    #[max_len(200)] pub response_uri: String,
+    pub status: DisputeStatus,
+    pub discount_bps: u16,
+    pub opened_at: i64,
+    pub resolved_at: Option<i64>,
+    pub resolver: Option<Pubkey>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum DisputeStatus {
+    Open,
+    Upheld,
+    Rejected,
+}
+
+/// Per-dimension rating totals, companion to AgentReputation
+#[account]
+#[derive(InitSpace)]
+pub struct AgentRatings {
+    pub agent_id: Pubkey,
+    // quality, reliability, speed, honesty
+    pub sums: [u64; RATING_DIMENSIONS],
+    pub counts: [u64; RATING_DIMENSIONS],
+    pub last_updated: i64,
+    pub bump: u8,
+}
+
+/// Evidence that a reviewer interacted with the agent, passed in `interaction_evidence`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum InteractionProof {
+    // ValidationRecord for the agent whose validator is the reviewer, co-signed by the agent authority
+    Validation,
+    // receipt `seq` with the reviewer as counterparty, proven against a ReceiptAnchor root (see receipt_leaf)
+    Receipt { seq: u64, receipt_hash: [u8; 32], proof: Vec<[u8; 32]> },
+}
+
+/// A reviewer's current verdict on an agent (one per pair)
 #[account]
 #[derive(InitSpace)]
-pub struct AgentReputation { pub agent_id: Pubkey, pub total_interactions: u64, pub positive_feedback: u64, pub negative_feedback: u64, pub reputation_score: u16, pub last_updated: i64, pub bump: u8 }
+pub struct FeedbackRecord {
+    pub agent_id: Pubkey,
+    pub reviewer: Pubkey,
+    pub positive: bool,
+    // weight applied to the aggregate, kept so edits and withdrawals undo it exactly
+    pub weight: u32,
+    // backed by a ValidationRecord or an anchored receipt
+    pub verified_interaction: bool,
+    // last submit_rating payload (0 = not rated), kept so edits and withdrawals undo it exactly
+    pub ratings: [u8; RATING_DIMENSIONS],
+    // set by an upheld dispute; applied to this reviewer's weight from then on
+    pub discount_bps: u16,
+    // category bucket this verdict also counts towards, fixed at creation
+    pub category: Option<u16>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
 
 #[account]
 #[derive(InitSpace)]
-pub struct ValidationRecord { pub agent_id: Pubkey, pub validator: Pubkey, pub task_hash: [u8; 32], pub passed: bool, #[max_len(200)] pub evidence_uri: String, pub timestamp: i64, pub bump: u8 }
+pub struct ValidationRecord { pub agent_id: Pubkey, pub validator: Pubkey, pub task_hash: [u8; 32], pub passed: bool, pub agent_cosigned: bool, #[max_len(200)] pub evidence_uri: String, pub timestamp: i64, pub bump: u8 }
 
 #[account]
 #[derive(InitSpace)]
-pub struct AgentStake { pub agent_id: Pubkey, pub amount: u64, pub staked_at: i64, pub cooldown_until: Option<i64>, pub is_slashed: bool, pub bump: u8 }
+pub struct AgentStake {
+    pub agent_id: Pubkey,
+    pub amount: u64,
+    pub staked_at: i64,
+    pub cooldown_until: Option<i64>,
+    pub is_slashed: bool,
+    // unstake payouts always go here; changes take WITHDRAWAL_ADDRESS_CHANGE_DELAY_SECS
+    pub withdrawal_address: Pubkey,
+    pub pending_withdrawal_address: Option<Pubkey>,
+    pub withdrawal_change_at: Option<i64>,
+    pub bump: u8,
+}
 
 #[account]
 #[derive(InitSpace)]
@@ -728,11 +3464,60 @@ pub struct ReceiptAnchor {
 
 #[event] pub struct AgentRegistered { pub agent_id: Pubkey, pub owner: Pubkey, pub metadata_uri: String }
 #[event] pub struct AgentVerified { pub agent_id: Pubkey, pub fee_paid: u64 }
+#[event] pub struct VerificationRenewed { pub agent_id: Pubkey, pub verified_until: i64, pub fee_paid: u64 }
+#[event] pub struct VerificationExpired { pub agent_id: Pubkey, pub verified_until: i64 }
+#[event] pub struct TierChanged { pub agent_id: Pubkey, pub old_tier: VerificationTier, pub new_tier: VerificationTier }
+#[event] pub struct VerificationRevoked { pub agent_id: Pubkey, pub reason: RevocationReason, pub source: Option<VerificationSource> }
+#[event] pub struct ReverificationFeeMultiplierUpdated { pub multiplier_bps: u16 }
+#[event] pub struct StakeVerificationLost { pub agent_id: Pubkey, pub stake_amount: u64 }
+#[event] pub struct StakeVerificationThresholdUpdated { pub lamports: u64 }
+#[event] pub struct TierThresholdsUpdated { pub secured: u64, pub professional: u64, pub enterprise: u64 }
 #[event] pub struct AgentUpdated { pub agent_id: Pubkey, pub new_metadata_uri: String }
 #[event] pub struct WalletLinked { pub agent_id: Pubkey, pub wallet: Pubkey, pub linked_by: Pubkey }
 #[event] pub struct WalletUnlinked { pub agent_id: Pubkey, pub wallet: Pubkey, pub unlinked_by: Pubkey }
+#[event] pub struct WalletMembershipAdded { pub agent_id: Pubkey, pub wallet: Pubkey, pub added_by: Pubkey }
+#[event] pub struct WalletMembershipRemoved { pub agent_id: Pubkey, pub wallet: Pubkey, pub removed_by: Pubkey }
+#[event] pub struct EvmWalletLinked { pub agent_id: Pubkey, pub evm_address: [u8; 20], pub linked_by: Pubkey }
+#[event] pub struct EvmWalletUnlinked { pub agent_id: Pubkey, pub evm_address: [u8; 20], pub unlinked_by: Pubkey }
 #[event] pub struct AuthorityTransferred { pub agent_id: Pubkey, pub old_authority: Pubkey, pub new_authority: Pubkey }
-#[event] pub struct FeedbackSubmitted { pub agent_id: Pubkey, pub from: Pubkey, pub positive: bool, pub context: String, pub new_score: u16 }
+#[event] pub struct AuthorityPolicyUpdated { pub agent_id: Pubkey, pub threshold: u8, pub members: Vec<Pubkey>, pub version: u32 }
+#[event] pub struct AuthorityPolicyRemoved { pub agent_id: Pubkey }
+#[event] pub struct PolicyActionProposed { pub agent_id: Pubkey, pub index: u64, pub proposer: Pubkey, pub action: PolicyAction }
+#[event] pub struct PolicyActionApproved { pub agent_id: Pubkey, pub index: u64, pub member: Pubkey, pub approvals: u8 }
+#[event] pub struct PolicyActionExecuted { pub agent_id: Pubkey, pub index: u64 }
+#[event] pub struct PolicyProposalClosed { pub agent_id: Pubkey, pub index: u64 }
+#[event] pub struct AgentSponsored { pub agent_id: Pubkey, pub sponsor: Pubkey }
+#[event] pub struct SponsoredIdentityAccepted { pub agent_id: Pubkey, pub owner: Pubkey }
+#[event] pub struct SponsoredIdentityRejected { pub agent_id: Pubkey, pub owner: Pubkey, pub sponsor: Pubkey }
+#[event] pub struct SponsorApproved { pub sponsor: Pubkey, pub label: String, pub registration_quota: u32, pub verification_quota: u32, pub expires_at: i64 }
+#[event] pub struct SponsorUpdated { pub sponsor: Pubkey, pub registration_quota: u32, pub verification_quota: u32, pub expires_at: i64 }
+#[event] pub struct SponsorRevoked { pub sponsor: Pubkey }
+#[event] pub struct BuildDeclared { pub agent_id: Pubkey, pub version: u32, pub framework: String, pub model: String, pub code_hash: [u8; 32] }
+#[event] pub struct BuildAttestationAttached { pub agent_id: Pubkey, pub version: u32, pub attestation: Pubkey, pub attester: Pubkey }
+#[event] pub struct BuildSchemaUpdated { pub schema_id: Option<[u8; 32]> }
+#[event] pub struct AttesterRegistered { pub attester: Pubkey, pub name: String, pub schemas: Vec<[u8; 32]> }
+#[event] pub struct AttesterUpdated { pub attester: Pubkey, pub schemas: Vec<[u8; 32]> }
+#[event] pub struct AttesterRevoked { pub attester: Pubkey }
+#[event] pub struct SchemaRegistered { pub id: [u8; 32], pub creator: Pubkey, pub field_count: u8, pub data_size: u16, pub revocable: bool, pub resolver: ResolverPolicy }
+#[event] pub struct AttestationIssued { pub agent_id: Pubkey, pub attester: Pubkey, pub schema_id: [u8; 32], pub data_hash: [u8; 32], pub expires_at: Option<i64> }
+#[event] pub struct AttestationUpdated { pub agent_id: Pubkey, pub attester: Pubkey, pub schema_id: [u8; 32], pub data_hash: [u8; 32], pub expires_at: Option<i64> }
+#[event] pub struct AttestationRevoked { pub agent_id: Pubkey, pub attester: Pubkey, pub schema_id: [u8; 32] }
+#[event] pub struct FeedbackWithdrawn { pub agent_id: Pubkey, pub from: Pubkey, pub positive: bool, pub new_score: u16 }
+#[event] pub struct FeedbackSubmitted { pub agent_id: Pubkey, pub from: Pubkey, pub positive: bool, pub context: String, pub new_score: u16, pub verified_interaction: bool }
+#[event] pub struct RatingSubmitted { pub agent_id: Pubkey, pub from: Pubkey, pub ratings: [u8; RATING_DIMENSIONS], pub averages: [u16; RATING_DIMENSIONS] }
+#[event] pub struct FeedbackDisputeOpened { pub agent_id: Pubkey, pub reviewer: Pubkey, pub response_uri: String }
+#[event] pub struct FeedbackDisputeResolved { pub agent_id: Pubkey, pub reviewer: Pubkey, pub upheld: bool, pub discount_bps: u16, pub new_score: u16 }
+#[event] pub struct ReputationSnapshotted { pub agent_id: Pubkey, pub timestamp: i64, pub score: u16, pub confidence_score: u16 }
+#[event] pub struct OracleRegistered { pub signer: Pubkey, pub name: String, pub max_staleness_secs: i64 }
+#[event] pub struct ReputationOracleUpdated { pub oracle: Option<Pubkey> }
+#[event] pub struct OracleUpdated { pub signer: Pubkey, pub active: bool, pub max_staleness_secs: i64 }
+#[event] pub struct ExternalReputationUpdated { pub agent_id: Pubkey, pub oracle: Pubkey, pub score_bps: u16, pub observed_at: i64, pub nonce: u64 }
+#[event] pub struct CategoryRegistered { pub id: u16, pub name: String }
+#[event] pub struct CategoryStatusChanged { pub id: u16, pub active: bool }
+#[event] pub struct CategoryReputationOpened { pub agent_id: Pubkey, pub category: u16 }
+#[event] pub struct ReputationPriorUpdated { pub score_bps: u16, pub weight: u32 }
+#[event] pub struct ArbiterUpdated { pub arbiter: Option<Pubkey> }
+#[event] pub struct FeedbackPolicyUpdated { pub agent_id: Pubkey, pub require_interaction_proof: bool }
 #[event] pub struct WorkValidated { pub agent_id: Pubkey, pub validator: Pubkey, pub task_hash: [u8; 32], pub passed: bool, pub evidence_uri: String }
 #[event] pub struct FeesWithdrawn { pub authority: Pubkey, pub amount: u64 }
 #[event] pub struct StakeDeposited { pub agent_id: Pubkey, pub amount: u64 }
@@ -740,5 +3525,167 @@ pub struct ReceiptAnchor {
 #[event] pub struct UnstakeCancelled { pub agent_id: Pubkey }
 #[event] pub struct Unstaked { pub agent_id: Pubkey, pub amount: u64 }
 #[event] pub struct EmergencyUnstaked { pub agent_id: Pubkey, pub payout: u64, pub penalty: u64 }
+#[event] pub struct WithdrawalAddressChangeRequested { pub agent_id: Pubkey, pub new_address: Pubkey, pub effective_at: i64 }
+#[event] pub struct WithdrawalAddressChanged { pub agent_id: Pubkey, pub old_address: Pubkey, pub new_address: Pubkey }
+#[event] pub struct WithdrawalAddressChangeCancelled { pub agent_id: Pubkey, pub cancelled_by: Pubkey }
 #[event] pub struct AgentSlashed { pub agent_id: Pubkey, pub amount: u64, pub severity_bps: u16 }
 #[event] pub struct AnchorSubmitted { pub agent_id: Pubkey, pub index: u64, pub start_seq: u64, pub end_seq: u64, pub root: [u8; 32] }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const H: i64 = REPUTATION_HALF_LIFE_SECS;
+    const TOLERANCE: u128 = 1_000_000; // 1e-6 in FIXED_ONE units
+
+    /// Account with every field zeroed (None, false, first enum variant, empty collections)
+    fn zeroed<T: AccountDeserialize>() -> T {
+        T::try_deserialize_unchecked(&mut &[0u8; 2048][..]).unwrap()
+    }
+
+    fn assert_close(actual: u128, expected: u128) {
+        assert!(actual.abs_diff(expected) <= TOLERANCE, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn log10_fixed_at_powers_of_ten() {
+        assert_eq!(log10_fixed(1, 1), 0);
+        assert_close(log10_fixed(10, 1), FIXED_ONE);
+        assert_close(log10_fixed(1_000, 10), 2 * FIXED_ONE);
+        assert_close(log10_fixed(10_000_000_000, 1), 10 * FIXED_ONE);
+    }
+
+    #[test]
+    fn log10_fixed_between_powers_of_ten() {
+        assert_close(log10_fixed(2, 1), LOG10_2);
+        assert_close(log10_fixed(5, 1), FIXED_ONE - LOG10_2);
+        assert_close(log10_fixed(3, 1), 477_121_254_720);
+        assert_close(log10_fixed(250, 100), 397_940_008_672);
+    }
+
+    #[test]
+    fn decay_factor_by_half_lives() {
+        assert_eq!(decay_factor(-1), FIXED_ONE);
+        assert_eq!(decay_factor(0), FIXED_ONE);
+        assert_eq!(decay_factor(H / 2), HALF_LIFE_ROOTS[0]);
+        assert_eq!(decay_factor(H), FIXED_ONE / 2);
+        assert_eq!(decay_factor(3 * H), FIXED_ONE / 8);
+        assert_eq!(decay_factor(40 * H), 0);
+    }
+
+    #[test]
+    fn stake_points_follow_log_curve_and_cap() {
+        assert_eq!(stake_points(MIN_STAKE_LAMPORTS - 1), 0);
+        assert_eq!(stake_points(MIN_STAKE_LAMPORTS), 1_500);
+        assert_eq!(stake_points(10 * MIN_STAKE_LAMPORTS), 1_750);
+        assert_eq!(stake_points(1_000 * MIN_STAKE_LAMPORTS), 2_250);
+        assert_eq!(stake_points(10_000 * MIN_STAKE_LAMPORTS), 2_500);
+        assert_eq!(stake_points(u64::MAX), 2_500);
+    }
+
+    /// 8 positive and 2 negative units of weight, decayed to t = 0
+    fn eighty_percent() -> AgentReputation {
+        let mut reputation: AgentReputation = zeroed();
+        for positive in [true, true, true, true, true, true, true, true, false, false] {
+            add_feedback(&mut reputation, positive, 1);
+        }
+        refresh_reputation_score(&mut reputation, &ReputationPrior { score_bps: 5_000, weight: 0 });
+        reputation
+    }
+
+    #[test]
+    fn reputation_points_halve_per_half_life() {
+        let mut reputation = eighty_percent();
+        assert_eq!(reputation.reputation_score, 8_000);
+        assert_eq!(reputation_points(&reputation, 0), 4_000);
+        assert_eq!(reputation_points(&reputation, H), 2_000);
+        assert_eq!(reputation_points(&reputation, 2 * H), 1_000);
+        reputation.decayed_negative = 0;
+        assert_eq!(reputation_points(&reputation, 0), 5_000);
+    }
+
+    #[test]
+    fn reputation_at_derives_from_decayed_accumulators() {
+        let reputation = eighty_percent();
+        let prior = ReputationPrior { score_bps: 5_000, weight: 10 };
+        let view = reputation_at(&reputation, &prior, H);
+        assert_eq!(view.decayed_positive, 4 * REPUTATION_DECAY_SCALE);
+        assert_eq!(view.decayed_negative, REPUTATION_DECAY_SCALE);
+        // the ratio survives decay, the fading score and the prior's pull do not
+        assert_eq!(view.score, 8_000);
+        assert_eq!(view.decayed_score, 4_000);
+        assert_eq!(view.confidence_score, 6_000);
+
+        let mut written = reputation.clone();
+        decay_reputation(&mut written, H);
+        refresh_reputation_score(&mut written, &prior);
+        assert_eq!((written.reputation_score, written.confidence_score), (view.score, view.confidence_score));
+    }
+
+    #[test]
+    fn removing_decayed_feedback_leaves_no_residue() {
+        let mut reputation: AgentReputation = zeroed();
+        let mut record: FeedbackRecord = zeroed();
+        record.positive = true;
+        record.weight = 3;
+        add_feedback(&mut reputation, true, 3);
+        add_feedback(&mut reputation, false, 1);
+        // decayed in uneven steps, while the record's share is decayed in one go
+        for now in [H / 3, H / 2, 2 * H + 7] {
+            decay_reputation(&mut reputation, now);
+        }
+        let expected = (3 * REPUTATION_DECAY_SCALE as u128 * decay_factor(2 * H + 7) / FIXED_ONE) as u64;
+        assert!(reputation.decayed_positive.abs_diff(expected) <= 2);
+
+        discount_feedback(&mut reputation, &record, 1, 2 * H + 7);
+        assert_eq!(reputation.weighted_positive, 2);
+        assert!(reputation.decayed_positive > 0);
+        record.weight = 2;
+        remove_feedback(&mut reputation, &record, 2 * H + 7);
+        assert_eq!(reputation.decayed_positive, 0);
+        // the negative side keeps its (equally step-decayed) weight
+        let negative = (REPUTATION_DECAY_SCALE as u128 * decay_factor(2 * H + 7) / FIXED_ONE) as u64;
+        assert!(reputation.decayed_negative.abs_diff(negative) <= 2);
+    }
+
+    #[test]
+    fn snapshots_follow_aligned_windows() {
+        const DAY: i64 = REPUTATION_SNAPSHOT_INTERVAL_SECS;
+        let now = 20_000 * DAY + 600;
+        assert!(snapshot_due(0, now));
+        assert!(!snapshot_due(now, now + 60));
+        // the window is aligned, not measured from the last snapshot
+        assert!(!snapshot_due(20_000 * DAY, 20_001 * DAY - 1));
+        assert!(snapshot_due(now, 20_001 * DAY));
+        assert!(snapshot_due(21_000 * DAY - 1, 21_000 * DAY));
+    }
+
+    #[test]
+    fn trust_score_breakdown() {
+        let mut agent: AgentIdentity = zeroed();
+        agent.is_verified = true;
+        agent.verification_tier = VerificationTier::Secured;
+        let mut stake: AgentStake = zeroed();
+        stake.amount = 10 * MIN_STAKE_LAMPORTS;
+        let reputation = eighty_percent();
+        let mut external: ExternalReputation = zeroed();
+        external.score_bps = 6_000;
+        external.stale_after = 100;
+
+        let fresh = trust_score(&agent, Some(&stake), Some(&reputation), Some(&external), 0);
+        assert_eq!((fresh.verification, fresh.stake, fresh.reputation), (1_500, 1_750, 3_500));
+        assert_eq!(fresh.total, 6_750);
+        assert!(!fresh.oracle_fallback);
+
+        // stale oracle score above neutral: the neutral score is assumed instead
+        external.stale_after = -1;
+        let stale = trust_score(&agent, Some(&stake), Some(&reputation), Some(&external), 0);
+        assert_eq!(stale.reputation, 3_250);
+        assert!(stale.oracle_fallback);
+
+        // expired verification and no stake or feedback count for nothing
+        agent.verified_until = Some(0);
+        let bare = trust_score(&agent, None, None, None, VERIFICATION_GRACE_PERIOD_SECS + 1);
+        assert_eq!(bare.total, 0);
+    }
+}
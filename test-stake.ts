@@ -1,42 +1,22 @@
 import * as anchor from "@coral-xyz/anchor";
-import { Program } from "@coral-xyz/anchor";
-import { PublicKey, SystemProgram, Keypair, Connection, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { PublicKey, Keypair, Connection, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import * as fs from "fs";
 
 const PROGRAM_ID = new PublicKey("ESPreFucjVwtDmZbhtL3JLJ9VxCethNEYtosMQhkcurv");
 const RPC = "https://api.devnet.solana.com";
 
-// Minimal IDL
-const IDL: anchor.Idl = {
-  version: "0.1.0",
-  name: "said",
-  instructions: [
-    {
-      name: "registerAndStake",
-      accounts: [
-        { name: "agentIdentity", isMut: true, isSigner: false },
-        { name: "agentStake", isMut: true, isSigner: false },
-        { name: "authority", isMut: true, isSigner: true },
-        { name: "treasury", isMut: true, isSigner: false },
-        { name: "systemProgram", isMut: false, isSigner: false },
-      ],
-      args: [
-        { name: "name", type: "string" },
-        { name: "stakeAmount", type: "u64" },
-      ],
-    },
-  ],
-};
+// IDL from `anchor build`, pointed at the devnet deployment
+const IDL = { ...JSON.parse(fs.readFileSync("target/idl/said.json", "utf8")), address: PROGRAM_ID.toBase58() };
 
 async function main() {
   const conn = new Connection(RPC, "confirmed");
   const walletPath = process.env.HOME + "/.config/solana/id.json";
   const secret = JSON.parse(fs.readFileSync(walletPath, "utf8"));
   const wallet = Keypair.fromSecretKey(Uint8Array.from(secret));
-  
+
   const provider = new anchor.AnchorProvider(conn, new anchor.Wallet(wallet), { commitment: "confirmed" });
-  const program = new anchor.Program(IDL, PROGRAM_ID, provider);
-  
+  const program = new anchor.Program(IDL as anchor.Idl, provider);
+
   // PDAs
   const [agentIdentity] = PublicKey.findProgramAddressSync(
     [Buffer.from("agent"), wallet.publicKey.toBuffer()],
@@ -46,27 +26,22 @@ async function main() {
     [Buffer.from("stake"), agentIdentity.toBuffer()],
     PROGRAM_ID
   );
-  const treasury = new PublicKey("Gnm9rzSWmzcSi6Pw59qHvpPE4PZNyqRpQYXGBzYgvNqq");
-  
+
   console.log("Wallet:", wallet.publicKey.toBase58());
   console.log("Agent PDA:", agentIdentity.toBase58());
   console.log("Stake PDA:", agentStake.toBase58());
-  
+
   const stakeAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL); // 0.1 SOL
-  
+  // unstake payouts always go here; changing it later takes 14 days
+  const withdrawalAddress = wallet.publicKey;
+
   try {
     const sig = await program.methods
-      .registerAndStake("TestAgent", stakeAmount)
-      .accounts({
-        agentIdentity,
-        agentStake,
-        authority: wallet.publicKey,
-        treasury,
-        systemProgram: SystemProgram.programId,
-      })
+      .registerAndStake("https://example.com/test-agent.json", stakeAmount, withdrawalAddress)
+      .accounts({ owner: wallet.publicKey })
       .signers([wallet])
       .rpc();
-    
+
     console.log("Success! Tx:", sig);
     console.log("Explorer: https://solscan.io/tx/" + sig + "?cluster=devnet");
   } catch (e: any) {
@@ -1,9 +1,12 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Said } from "../target/types/said";
-import { Keypair, PublicKey, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { Ed25519Program, Keypair, PublicKey, SystemProgram, LAMPORTS_PER_SOL, SYSVAR_INSTRUCTIONS_PUBKEY } from "@solana/web3.js";
 import { expect } from "chai";
 import crypto from "crypto";
+import fs from "fs";
+import { secp256k1 } from "@noble/curves/secp256k1";
+import { keccak_256 } from "@noble/hashes/sha3";
 
 describe("said", () => {
   const provider = anchor.AnchorProvider.env();
@@ -17,6 +20,20 @@ describe("said", () => {
   const walletD = Keypair.generate();
   const randomWallet = Keypair.generate();
 
+  // Treasury, config and other admin-gated flows need the TREASURY_AUTHORITY key from lib.rs.
+  // Point SAID_ADMIN_KEYPAIR at its keypair file to run them; otherwise those blocks are skipped
+  // locally and fail under CI, so a missing secret can't silently drop coverage.
+  const admin: Keypair | null = process.env.SAID_ADMIN_KEYPAIR
+    ? Keypair.fromSecretKey(Uint8Array.from(JSON.parse(fs.readFileSync(process.env.SAID_ADMIN_KEYPAIR, "utf8"))))
+    : null;
+
+  function skipWithoutAdmin(ctx: Mocha.Context) {
+    if (process.env.CI) {
+      throw new Error("SAID_ADMIN_KEYPAIR is not set; admin-gated tests cannot run in CI");
+    }
+    ctx.skip();
+  }
+
   // PDA helpers
   function agentPda(wallet: PublicKey): [PublicKey, number] {
     return PublicKey.findProgramAddressSync(
@@ -46,6 +63,55 @@ describe("said", () => {
     );
   }
 
+  function stakePda(agentId: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), agentId.toBuffer()],
+      program.programId
+    );
+  }
+
+  function configPda(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+  }
+
+  // Attestation data hash: keccak over each field as a u32-LE length prefix followed by its bytes
+  function dataHash(fields: Buffer[]): number[] {
+    const parts = fields.flatMap((f) => {
+      const len = Buffer.alloc(4);
+      len.writeUInt32LE(f.length);
+      return [len, f];
+    });
+    return Array.from(keccak_256(Buffer.concat(parts)));
+  }
+
+  async function airdrop(keypairs: Keypair[], sol = 5) {
+    await Promise.all(keypairs.map(async (kp) => {
+      const sig = await provider.connection.requestAirdrop(kp.publicKey, sol * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+    }));
+  }
+
+  // Treasury and config PDAs, created by whichever admin-gated block runs first
+  async function ensureProtocolAccounts(adminKp: Keypair) {
+    await airdrop([adminKp], 100);
+    const [treasury] = PublicKey.findProgramAddressSync([Buffer.from("treasury")], program.programId);
+    if (!(await provider.connection.getAccountInfo(treasury))) {
+      await program.methods.initializeTreasury().accounts({ authority: adminKp.publicKey }).signers([adminKp]).rpc();
+    }
+    if (!(await provider.connection.getAccountInfo(configPda()[0]))) {
+      await program.methods.initializeConfig().accounts({ authority: adminKp.publicKey }).signers([adminKp]).rpc();
+    }
+  }
+
+  async function registerVerified(kp: Keypair, uri: string) {
+    await program.methods.registerAgent(uri).accounts({ owner: kp.publicKey }).signers([kp]).rpc();
+    await program.methods
+      .getVerified()
+      .accounts({ agentIdentity: agentPda(kp.publicKey)[0], authority: kp.publicKey })
+      .signers([kp])
+      .rpc();
+  }
+
   before(async () => {
     // Airdrop to all test wallets
     const airdrops = [owner, walletB, walletC, walletD, randomWallet].map(async (kp) => {
@@ -243,6 +309,309 @@ describe("said", () => {
     });
   });
 
+  // ==================== SIGNATURE-BASED LINKING ====================
+
+  describe("link_wallet_with_signature", () => {
+    const coldWallet = Keypair.generate();
+    const otherColdWallet = Keypair.generate();
+
+    function linkMessage(agentId: PublicKey, wallet: PublicKey, nonce: anchor.BN, expiresAt: anchor.BN): Buffer {
+      return Buffer.concat([
+        Buffer.from("SAID:link_wallet:v1"),
+        program.programId.toBuffer(),
+        agentId.toBuffer(),
+        wallet.toBuffer(),
+        nonce.toArrayLike(Buffer, "le", 8),
+        expiresAt.toTwos(64).toArrayLike(Buffer, "le", 8),
+      ]);
+    }
+
+    async function linkWithSignature(signer: Keypair, wallet: PublicKey, nonce: anchor.BN) {
+      const [identityPda] = agentPda(owner.publicKey);
+      const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 600);
+      const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: linkMessage(identityPda, wallet, nonce, expiresAt),
+      });
+      await program.methods
+        .linkWalletWithSignature(nonce, expiresAt)
+        .accounts({
+          agentIdentity: identityPda,
+          authority: owner.publicKey,
+          newWallet: wallet,
+          instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .preInstructions([ed25519Ix])
+        .signers([owner])
+        .rpc();
+    }
+
+    it("links a cold wallet without it signing the transaction", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+
+      await linkWithSignature(coldWallet, coldWallet.publicKey, identity.linkNonce);
+
+      const [linkPda] = walletLinkPda(coldWallet.publicKey);
+      const link = await program.account.walletLink.fetch(linkPda);
+      expect(link.agentId.toBase58()).to.equal(identityPda.toBase58());
+      const updated = await program.account.agentIdentity.fetch(identityPda);
+      expect(updated.linkNonce.toNumber()).to.equal(identity.linkNonce.toNumber() + 1);
+    });
+
+    it("rejects a reused nonce", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+
+      try {
+        await linkWithSignature(otherColdWallet, otherColdWallet.publicKey, identity.linkNonce.subn(1));
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("InvalidLinkNonce");
+      }
+    });
+
+    it("rejects a signature from a different key", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+
+      try {
+        await linkWithSignature(randomWallet, otherColdWallet.publicKey, identity.linkNonce);
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("InvalidEd25519Instruction");
+      }
+    });
+  });
+
+  // ==================== EVM WALLET LINKING ====================
+
+  describe("link_evm_wallet", () => {
+    const evmOwner = Keypair.generate();
+    const evmKey = secp256k1.utils.randomPrivateKey();
+    const evmAddress = Buffer.from(keccak_256(secp256k1.getPublicKey(evmKey, false).slice(1)).slice(12));
+
+    function evmLinkPda(address: Buffer): [PublicKey, number] {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("evm_wallet"), address],
+        program.programId
+      );
+    }
+
+    // EIP-191 personal_sign over evm_link_message, split into the 64-byte signature and recovery id
+    function signEvmLink(key: Uint8Array, agentId: PublicKey, nonce: anchor.BN): [number[], number] {
+      const message = Buffer.from(`SAID Protocol: link EVM wallet\nagent: ${agentId.toBase58()}\nnonce: ${nonce.toString()}`);
+      const prefix = Buffer.from(`\x19Ethereum Signed Message:\n${message.length}`);
+      const sig = secp256k1.sign(keccak_256(Buffer.concat([prefix, message])), key);
+      return [[...sig.toCompactRawBytes()], sig.recovery + 27];
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(evmOwner.publicKey, 5 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await program.methods
+        .registerAgent("https://example.com/evm-agent.json")
+        .accounts({ owner: evmOwner.publicKey })
+        .signers([evmOwner])
+        .rpc();
+    });
+
+    it("rejects a signature from a different EVM key", async () => {
+      const [identityPda] = agentPda(evmOwner.publicKey);
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      const [signature, recoveryId] = signEvmLink(secp256k1.utils.randomPrivateKey(), identityPda, identity.linkNonce);
+
+      try {
+        await program.methods
+          .linkEvmWallet([...evmAddress], identity.linkNonce, signature, recoveryId)
+          .accounts({ agentIdentity: identityPda, authority: evmOwner.publicKey })
+          .signers([evmOwner])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("InvalidEvmSignature");
+      }
+    });
+
+    it("rejects recovery ids other than 0, 1, 27 and 28", async () => {
+      const [identityPda] = agentPda(evmOwner.publicKey);
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      const [signature, recoveryId] = signEvmLink(evmKey, identityPda, identity.linkNonce);
+
+      for (const badId of [recoveryId - 27 + 2, recoveryId + 2, 255]) {
+        try {
+          await program.methods
+            .linkEvmWallet([...evmAddress], identity.linkNonce, signature, badId)
+            .accounts({ agentIdentity: identityPda, authority: evmOwner.publicKey })
+            .signers([evmOwner])
+            .rpc();
+          expect.fail("should have thrown");
+        } catch (e: any) {
+          expect(e.toString()).to.contain("InvalidRecoveryId");
+        }
+      }
+    });
+
+    it("links an EVM address proven by an EIP-191 signature", async () => {
+      const [identityPda] = agentPda(evmOwner.publicKey);
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      const [signature, recoveryId] = signEvmLink(evmKey, identityPda, identity.linkNonce);
+
+      await program.methods
+        .linkEvmWallet([...evmAddress], identity.linkNonce, signature, recoveryId)
+        .accounts({ agentIdentity: identityPda, authority: evmOwner.publicKey })
+        .signers([evmOwner])
+        .rpc();
+
+      const link = await program.account.evmWalletLink.fetch(evmLinkPda(evmAddress)[0]);
+      expect(link.agentId.toBase58()).to.equal(identityPda.toBase58());
+      expect(Buffer.from(link.evmAddress).equals(evmAddress)).to.be.true;
+      const updated = await program.account.agentIdentity.fetch(identityPda);
+      expect(updated.linkNonce.toNumber()).to.equal(identity.linkNonce.toNumber() + 1);
+    });
+
+    it("rejects replaying the signature for a stale nonce", async () => {
+      const [identityPda] = agentPda(evmOwner.publicKey);
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      const staleNonce = identity.linkNonce.subn(1);
+      const [signature, recoveryId] = signEvmLink(evmKey, identityPda, staleNonce);
+
+      try {
+        await program.methods
+          .linkEvmWallet([...evmAddress], staleNonce, signature, recoveryId)
+          .accounts({ agentIdentity: identityPda, authority: evmOwner.publicKey })
+          .signers([evmOwner])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("InvalidLinkNonce");
+      }
+    });
+
+    it("non-authority cannot unlink", async () => {
+      const [identityPda] = agentPda(evmOwner.publicKey);
+
+      try {
+        await program.methods
+          .unlinkEvmWallet()
+          .accounts({ agentIdentity: identityPda, evmWalletLink: evmLinkPda(evmAddress)[0], authority: randomWallet.publicKey })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("Unauthorized");
+      }
+    });
+
+    it("authority unlinks the EVM address", async () => {
+      const [identityPda] = agentPda(evmOwner.publicKey);
+      const [linkPda] = evmLinkPda(evmAddress);
+
+      await program.methods
+        .unlinkEvmWallet()
+        .accounts({ agentIdentity: identityPda, evmWalletLink: linkPda, authority: evmOwner.publicKey })
+        .signers([evmOwner])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(linkPda)).to.be.null;
+    });
+  });
+
+  // ==================== WALLET MEMBERSHIPS ====================
+
+  describe("wallet memberships", () => {
+    const teamA = Keypair.generate();
+    const teamB = Keypair.generate();
+    const sharedWallet = Keypair.generate();
+
+    function membershipPda(wallet: PublicKey, agentId: PublicKey): [PublicKey, number] {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("membership"), wallet.toBuffer(), agentId.toBuffer()],
+        program.programId
+      );
+    }
+
+    before(async () => {
+      const airdrops = [teamA, teamB, sharedWallet].map(async (kp) => {
+        const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+      });
+      await Promise.all(airdrops);
+      for (const team of [teamA, teamB]) {
+        await program.methods
+          .registerAgent("https://example.com/team-agent.json")
+          .accounts({ owner: team.publicKey })
+          .signers([team])
+          .rpc();
+      }
+    });
+
+    it("one wallet joins two identities", async () => {
+      for (const team of [teamA, teamB]) {
+        const [identityPda] = agentPda(team.publicKey);
+        await program.methods
+          .addWalletMembership()
+          .accounts({ agentIdentity: identityPda, authority: team.publicKey, wallet: sharedWallet.publicKey })
+          .signers([team, sharedWallet])
+          .rpc();
+
+        const membership = await program.account.walletMembership.fetch(membershipPda(sharedWallet.publicKey, identityPda)[0]);
+        expect(membership.agentId.toBase58()).to.equal(identityPda.toBase58());
+        expect(membership.wallet.toBase58()).to.equal(sharedWallet.publicKey.toBase58());
+      }
+    });
+
+    it("membership grants no authority", async () => {
+      const [identityPda] = agentPda(teamA.publicKey);
+
+      try {
+        await program.methods
+          .updateAgent("https://example.com/member-edit.json")
+          .accounts({ agentIdentity: identityPda, authority: sharedWallet.publicKey })
+          .signers([sharedWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("Unauthorized");
+      }
+    });
+
+    it("outsiders cannot remove a membership", async () => {
+      const [identityPda] = agentPda(teamA.publicKey);
+
+      try {
+        await program.methods
+          .removeWalletMembership()
+          .accounts({
+            agentIdentity: identityPda,
+            walletMembership: membershipPda(sharedWallet.publicKey, identityPda)[0],
+            caller: randomWallet.publicKey,
+          })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("Unauthorized");
+      }
+    });
+
+    it("the member leaves one identity and keeps the other", async () => {
+      const [identityA] = agentPda(teamA.publicKey);
+      const [identityB] = agentPda(teamB.publicKey);
+      const [membershipA] = membershipPda(sharedWallet.publicKey, identityA);
+
+      await program.methods
+        .removeWalletMembership()
+        .accounts({ agentIdentity: identityA, walletMembership: membershipA, caller: sharedWallet.publicKey })
+        .signers([sharedWallet])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(membershipA)).to.be.null;
+      const remaining = await program.account.walletMembership.fetch(membershipPda(sharedWallet.publicKey, identityB)[0]);
+      expect(remaining.agentId.toBase58()).to.equal(identityB.toBase58());
+    });
+  });
+
   // ==================== TRANSFER AUTHORITY ====================
 
   describe("transfer_authority", () => {
@@ -509,90 +878,2414 @@ describe("said", () => {
     });
   });
 
-  // ==================== FEEDBACK & REPUTATION ====================
+  // ==================== MULTISIG AUTHORITY ====================
 
-  describe("submit_feedback", () => {
-    it("submits positive feedback", async () => {
-      const [identityPda] = agentPda(owner.publicKey);
-      const [repPda] = reputationPda(identityPda);
+  describe("authority policy: 2-of-2 multisig", () => {
+    const msOwner = Keypair.generate();
+    const msMember = Keypair.generate();
+
+    function policyPda(agentId: PublicKey): [PublicKey, number] {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("policy"), agentId.toBuffer()],
+        program.programId
+      );
+    }
+
+    function proposalPda(agentId: PublicKey, index: number): [PublicKey, number] {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("proposal"), agentId.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+    }
+
+    before(async () => {
+      const airdrops = [msOwner, msMember].map(async (kp) => {
+        const sig = await provider.connection.requestAirdrop(kp.publicKey, 5 * LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+      });
+      await Promise.all(airdrops);
 
+      const [identityPda] = agentPda(msOwner.publicKey);
       await program.methods
-        .submitFeedback(true, "good trade on torch.market")
+        .registerAgent("https://example.com/multisig-agent.json")
+        .accounts({ owner: msOwner.publicKey })
+        .signers([msOwner])
+        .rpc();
+      await program.methods
+        .linkWallet()
         .accounts({
           agentIdentity: identityPda,
-          reviewer: randomWallet.publicKey,
+          authority: msOwner.publicKey,
+          newWallet: msMember.publicKey,
         })
-        .signers([randomWallet])
+        .signers([msOwner, msMember])
         .rpc();
-
-      const rep = await program.account.agentReputation.fetch(repPda);
-      expect(rep.totalInteractions.toNumber()).to.equal(1);
-      expect(rep.positiveFeedback.toNumber()).to.equal(1);
-      expect(rep.negativeFeedback.toNumber()).to.equal(0);
-      expect(rep.reputationScore).to.equal(10000); // 100%
     });
 
-    it("submits negative feedback, score updates", async () => {
-      const [identityPda] = agentPda(owner.publicKey);
-      const [repPda] = reputationPda(identityPda);
+    it("authority enables a 2-of-2 policy with a linked wallet", async () => {
+      const [identityPda] = agentPda(msOwner.publicKey);
+      const [memberLink] = walletLinkPda(msMember.publicKey);
 
       await program.methods
-        .submitFeedback(false, "failed to deliver")
+        .createAuthorityPolicy(2, [msOwner.publicKey, msMember.publicKey])
         .accounts({
           agentIdentity: identityPda,
-          reviewer: walletD.publicKey,
+          authority: msOwner.publicKey,
         })
-        .signers([walletD])
+        .remainingAccounts([{ pubkey: memberLink, isSigner: false, isWritable: false }])
+        .signers([msOwner])
         .rpc();
 
-      const rep = await program.account.agentReputation.fetch(repPda);
-      expect(rep.totalInteractions.toNumber()).to.equal(2);
-      expect(rep.positiveFeedback.toNumber()).to.equal(1);
-      expect(rep.negativeFeedback.toNumber()).to.equal(1);
-      expect(rep.reputationScore).to.equal(5000); // 50%
+      const policy = await program.account.authorityPolicy.fetch(policyPda(identityPda)[0]);
+      expect(policy.threshold).to.equal(2);
+      expect(policy.members.length).to.equal(2);
     });
-  });
 
-  // ==================== WORK VALIDATION ====================
+    it("authority alone can no longer update metadata", async () => {
+      const [identityPda] = agentPda(msOwner.publicKey);
 
-  describe("validate_work", () => {
-    it("validates agent work with task hash", async () => {
-      const [identityPda] = agentPda(owner.publicKey);
-      const taskHash = crypto.createHash("sha256").update("task-001").digest();
-      const [valPda] = validationPda(identityPda, taskHash);
+      try {
+        await program.methods
+          .updateAgent("https://example.com/multisig-agent-v2.json")
+          .accounts({
+            agentIdentity: identityPda,
+            authority: msOwner.publicKey,
+            authorityPolicy: policyPda(identityPda)[0],
+            policyProposal: null,
+          })
+          .signers([msOwner])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("PolicyApprovalRequired");
+      }
+    });
+
+    it("update executes once both members approve", async () => {
+      const [identityPda] = agentPda(msOwner.publicKey);
+      const [policy] = policyPda(identityPda);
+      const [proposal] = proposalPda(identityPda, 0);
+      const uri = "https://example.com/multisig-agent-v2.json";
 
       await program.methods
-        .validateWork([...taskHash] as any, true, "https://example.com/evidence")
+        .proposePolicyAction({ updateAgent: { newMetadataUri: uri } })
+        .accounts({ authorityPolicy: policy, proposer: msOwner.publicKey })
+        .signers([msOwner])
+        .rpc();
+      await program.methods
+        .approvePolicyAction()
+        .accounts({ authorityPolicy: policy, policyProposal: proposal, member: msMember.publicKey })
+        .signers([msMember])
+        .rpc();
+      await program.methods
+        .updateAgent(uri)
         .accounts({
           agentIdentity: identityPda,
-          validator: randomWallet.publicKey,
+          authority: msOwner.publicKey,
+          authorityPolicy: policy,
+          policyProposal: proposal,
         })
-        .signers([randomWallet])
+        .remainingAccounts([{ pubkey: walletLinkPda(msMember.publicKey)[0], isSigner: false, isWritable: false }])
+        .signers([msOwner])
         .rpc();
 
-      const record = await program.account.validationRecord.fetch(valPda);
-      expect(record.agentId.toBase58()).to.equal(identityPda.toBase58());
-      expect(record.validator.toBase58()).to.equal(randomWallet.publicKey.toBase58());
-      expect(record.passed).to.be.true;
-      expect(record.evidenceUri).to.equal("https://example.com/evidence");
+      const account = await program.account.agentIdentity.fetch(identityPda);
+      expect(account.metadataUri).to.equal(uri);
+      const executed = await program.account.authorityProposal.fetch(proposal);
+      expect(executed.executed).to.be.true;
     });
 
-    it("cannot validate same task twice", async () => {
-      const [identityPda] = agentPda(owner.publicKey);
-      const taskHash = crypto.createHash("sha256").update("task-001").digest();
+    it("proposer closes an executed proposal", async () => {
+      const [identityPda] = agentPda(msOwner.publicKey);
+      const [proposal] = proposalPda(identityPda, 0);
+
+      await program.methods
+        .closePolicyProposal()
+        .accounts({ authorityPolicy: policyPda(identityPda)[0], policyProposal: proposal, proposer: msOwner.publicKey })
+        .signers([msOwner])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(proposal)).to.be.null;
+    });
+
+    it("a pending proposal cannot be closed", async () => {
+      const [identityPda] = agentPda(msOwner.publicKey);
+      const [policy] = policyPda(identityPda);
+      const [proposal] = proposalPda(identityPda, 1);
+
+      await program.methods
+        .proposePolicyAction({ updateAgent: { newMetadataUri: "https://example.com/multisig-agent-v3.json" } })
+        .accounts({ authorityPolicy: policy, proposer: msOwner.publicKey })
+        .signers([msOwner])
+        .rpc();
 
       try {
         await program.methods
-          .validateWork([...taskHash] as any, false, "https://example.com/evidence2")
-          .accounts({
-            agentIdentity: identityPda,
-            validator: walletD.publicKey,
-          })
-          .signers([walletD])
+          .closePolicyProposal()
+          .accounts({ authorityPolicy: policy, policyProposal: proposal, proposer: msOwner.publicKey })
+          .signers([msOwner])
           .rpc();
         expect.fail("should have thrown");
       } catch (e: any) {
-        expect(e.toString()).to.contain("already in use");
+        expect(e.toString()).to.contain("ProposalStillPending");
+      }
+    });
+
+    it("an approval from a since-unlinked member no longer counts", async () => {
+      const [identityPda] = agentPda(msOwner.publicKey);
+      const [policy] = policyPda(identityPda);
+      const [proposal] = proposalPda(identityPda, 1);
+      const [memberLink] = walletLinkPda(msMember.publicKey);
+
+      await program.methods
+        .approvePolicyAction()
+        .accounts({ authorityPolicy: policy, policyProposal: proposal, member: msMember.publicKey })
+        .signers([msMember])
+        .rpc();
+      await program.methods
+        .unlinkWallet()
+        .accounts({ agentIdentity: identityPda, walletLink: memberLink, caller: msMember.publicKey })
+        .signers([msMember])
+        .rpc();
+
+      try {
+        await program.methods
+          .updateAgent("https://example.com/multisig-agent-v3.json")
+          .accounts({
+            agentIdentity: identityPda,
+            authority: msOwner.publicKey,
+            authorityPolicy: policy,
+            policyProposal: proposal,
+          })
+          .remainingAccounts([{ pubkey: memberLink, isSigner: false, isWritable: false }])
+          .signers([msOwner])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("ThresholdNotMet");
+      }
+    });
+  });
+
+  // ==================== STAKING ====================
+
+  describe("staking: withdrawal address", () => {
+    const staker = Keypair.generate();
+    const payoutWallet = Keypair.generate();
+    const bundled = Keypair.generate();
+    const attackerAddress = Keypair.generate().publicKey;
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([staker, payoutWallet, bundled], 10);
+      await registerVerified(staker, "https://example.com/staker.json");
+    });
+
+    it("stake records the withdrawal address", async () => {
+      const [identityPda] = agentPda(staker.publicKey);
+
+      await program.methods
+        .stake(new anchor.BN(0.1 * LAMPORTS_PER_SOL), payoutWallet.publicKey)
+        .accounts({ agentIdentity: identityPda, authority: staker.publicKey })
+        .signers([staker])
+        .rpc();
+
+      const stake = await program.account.agentStake.fetch(stakePda(identityPda)[0]);
+      expect(stake.amount.toNumber()).to.equal(0.1 * LAMPORTS_PER_SOL);
+      expect(stake.withdrawalAddress.toBase58()).to.equal(payoutWallet.publicKey.toBase58());
+      expect(stake.pendingWithdrawalAddress).to.be.null;
+    });
+
+    it("register_and_stake records the withdrawal address", async () => {
+      const [identityPda] = agentPda(bundled.publicKey);
+
+      await program.methods
+        .registerAndStake("https://example.com/bundled.json", new anchor.BN(0.1 * LAMPORTS_PER_SOL), payoutWallet.publicKey)
+        .accounts({ owner: bundled.publicKey })
+        .signers([bundled])
+        .rpc();
+
+      const stake = await program.account.agentStake.fetch(stakePda(identityPda)[0]);
+      expect(stake.withdrawalAddress.toBase58()).to.equal(payoutWallet.publicKey.toBase58());
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.isVerified).to.be.true;
+    });
+
+    it("rejects the default pubkey as withdrawal address", async () => {
+      const fresh = Keypair.generate();
+      await airdrop([fresh]);
+
+      try {
+        await program.methods
+          .registerAndStake("https://example.com/no-payout.json", new anchor.BN(0.1 * LAMPORTS_PER_SOL), PublicKey.default)
+          .accounts({ owner: fresh.publicKey })
+          .signers([fresh])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("InvalidWithdrawalAddress");
+      }
+    });
+
+    it("a requested change only completes after the delay", async () => {
+      const [identityPda] = agentPda(staker.publicKey);
+
+      await program.methods
+        .requestWithdrawalAddressChange(attackerAddress)
+        .accounts({ agentIdentity: identityPda, authority: staker.publicKey })
+        .signers([staker])
+        .rpc();
+
+      const stake = await program.account.agentStake.fetch(stakePda(identityPda)[0]);
+      expect(stake.pendingWithdrawalAddress.toBase58()).to.equal(attackerAddress.toBase58());
+
+      try {
+        await program.methods
+          .completeWithdrawalAddressChange()
+          .accounts({ agentIdentity: identityPda, authority: staker.publicKey })
+          .signers([staker])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("WithdrawalChangeDelayNotElapsed");
+      }
+    });
+
+    it("outsiders cannot cancel a pending change", async () => {
+      const [identityPda] = agentPda(staker.publicKey);
+
+      try {
+        await program.methods
+          .cancelWithdrawalAddressChange()
+          .accounts({ agentIdentity: identityPda, caller: randomWallet.publicKey })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("Unauthorized");
+      }
+    });
+
+    it("the current withdrawal address cancels a change made with a hijacked authority", async () => {
+      const [identityPda] = agentPda(staker.publicKey);
+
+      await program.methods
+        .cancelWithdrawalAddressChange()
+        .accounts({ agentIdentity: identityPda, caller: payoutWallet.publicKey })
+        .signers([payoutWallet])
+        .rpc();
+
+      const stake = await program.account.agentStake.fetch(stakePda(identityPda)[0]);
+      expect(stake.pendingWithdrawalAddress).to.be.null;
+      expect(stake.withdrawalChangeAt).to.be.null;
+      expect(stake.withdrawalAddress.toBase58()).to.equal(payoutWallet.publicKey.toBase58());
+    });
+
+    it("the authority can cancel its own request", async () => {
+      const [identityPda] = agentPda(staker.publicKey);
+
+      await program.methods
+        .requestWithdrawalAddressChange(attackerAddress)
+        .accounts({ agentIdentity: identityPda, authority: staker.publicKey })
+        .signers([staker])
+        .rpc();
+      await program.methods
+        .cancelWithdrawalAddressChange()
+        .accounts({ agentIdentity: identityPda, caller: staker.publicKey })
+        .signers([staker])
+        .rpc();
+
+      const stake = await program.account.agentStake.fetch(stakePda(identityPda)[0]);
+      expect(stake.pendingWithdrawalAddress).to.be.null;
+    });
+
+    it("can stake again after unstaking", async () => {
+      const [identityPda] = agentPda(staker.publicKey);
+
+      await program.methods
+        .emergencyUnstake()
+        .accountsPartial({
+          agentIdentity: identityPda,
+          withdrawalAddress: payoutWallet.publicKey,
+          authorityPolicy: null,
+          policyProposal: null,
+          authority: staker.publicKey,
+        })
+        .signers([staker])
+        .rpc();
+      expect((await program.account.agentStake.fetch(stakePda(identityPda)[0])).amount.toNumber()).to.equal(0);
+
+      await program.methods
+        .stake(new anchor.BN(0.2 * LAMPORTS_PER_SOL), payoutWallet.publicKey)
+        .accounts({ agentIdentity: identityPda, authority: staker.publicKey })
+        .signers([staker])
+        .rpc();
+
+      const stake = await program.account.agentStake.fetch(stakePda(identityPda)[0]);
+      expect(stake.amount.toNumber()).to.equal(0.2 * LAMPORTS_PER_SOL);
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.stakeAmount.toNumber()).to.equal(0.2 * LAMPORTS_PER_SOL);
+    });
+
+    it("an active stake cannot be staked over", async () => {
+      try {
+        await program.methods
+          .stake(new anchor.BN(0.1 * LAMPORTS_PER_SOL), payoutWallet.publicKey)
+          .accounts({ agentIdentity: agentPda(staker.publicKey)[0], authority: staker.publicKey })
+          .signers([staker])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("AlreadyStaked");
+      }
+    });
+  });
+
+  describe("staking: tiers follow the config thresholds", () => {
+    const climber = Keypair.generate();
+    const SOL = (n: number) => new anchor.BN(n * LAMPORTS_PER_SOL);
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([climber], 10);
+      await program.methods
+        .registerAgent("https://example.com/climber.json")
+        .accounts({ owner: climber.publicKey })
+        .signers([climber])
+        .rpc();
+    });
+
+    after(async function () {
+      if (!admin) return;
+      await program.methods
+        .setTierThresholds(SOL(0.1), SOL(1), SOL(10))
+        .accounts({ authority: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    });
+
+    it("rejects thresholds out of order", async () => {
+      try {
+        await program.methods
+          .setTierThresholds(SOL(1), SOL(0.5), SOL(2))
+          .accounts({ authority: admin.publicKey })
+          .signers([admin])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("InvalidTierThresholds");
+      }
+    });
+
+    it("only the admin can set thresholds", async () => {
+      try {
+        await program.methods
+          .setTierThresholds(SOL(0.1), SOL(0.2), SOL(0.5))
+          .accounts({ authority: randomWallet.publicKey })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("UnauthorizedAuthority");
+      }
+    });
+
+    it("verification moves Registered to Verified", async () => {
+      const [identityPda] = agentPda(climber.publicKey);
+      let identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.verificationTier).to.deep.equal({ registered: {} });
+
+      await program.methods
+        .setTierThresholds(SOL(0.1), SOL(0.2), SOL(0.5))
+        .accounts({ authority: admin.publicKey })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .getVerified()
+        .accounts({ agentIdentity: identityPda, authority: climber.publicKey })
+        .signers([climber])
+        .rpc();
+
+      identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.verificationTier).to.deep.equal({ verified: {} });
+    });
+
+    it("stake at the secured threshold gives Secured", async () => {
+      const [identityPda] = agentPda(climber.publicKey);
+
+      await program.methods
+        .stake(SOL(0.1), climber.publicKey)
+        .accounts({ agentIdentity: identityPda, authority: climber.publicKey })
+        .signers([climber])
+        .rpc();
+
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.verificationTier).to.deep.equal({ secured: {} });
+    });
+
+    it("add_stake climbs through Professional and Enterprise", async () => {
+      const [identityPda] = agentPda(climber.publicKey);
+
+      await program.methods
+        .addStake(SOL(0.1))
+        .accounts({ agentIdentity: identityPda, authority: climber.publicKey })
+        .signers([climber])
+        .rpc();
+      let identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.verificationTier).to.deep.equal({ professional: {} });
+
+      await program.methods
+        .addStake(SOL(0.3))
+        .accounts({ agentIdentity: identityPda, authority: climber.publicKey })
+        .signers([climber])
+        .rpc();
+      identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.verificationTier).to.deep.equal({ enterprise: {} });
+      expect(identity.stakeAmount.toNumber()).to.equal(0.5 * LAMPORTS_PER_SOL);
+    });
+  });
+
+  describe("staking: verify_by_stake", () => {
+    const fresh = Keypair.generate();
+    const topUp = Keypair.generate();
+    const payout = Keypair.generate().publicKey;
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([fresh, topUp], 10);
+      await program.methods
+        .registerAgent("https://example.com/stake-verified.json")
+        .accounts({ owner: fresh.publicKey })
+        .signers([fresh])
+        .rpc();
+      // an existing, half-slashed stake whose verification was revoked
+      await registerVerified(topUp, "https://example.com/top-up.json");
+      await program.methods
+        .stake(new anchor.BN(0.1 * LAMPORTS_PER_SOL), payout)
+        .accounts({ agentIdentity: agentPda(topUp.publicKey)[0], authority: topUp.publicKey })
+        .signers([topUp])
+        .rpc();
+      await program.methods
+        .slashAgent(5_000)
+        .accounts({ agentIdentity: agentPda(topUp.publicKey)[0], admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .revokeVerification({ other: {} })
+        .accounts({ agentIdentity: agentPda(topUp.publicKey)[0], admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    });
+
+    it("rejects a stake below the threshold", async () => {
+      try {
+        await program.methods
+          .verifyByStake(new anchor.BN(0.4 * LAMPORTS_PER_SOL), fresh.publicKey)
+          .accounts({ agentIdentity: agentPda(fresh.publicKey)[0], authority: fresh.publicKey })
+          .signers([fresh])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("StakeTooLow");
+      }
+    });
+
+    it("verifies a new stake at the threshold", async () => {
+      const [identityPda] = agentPda(fresh.publicKey);
+
+      await program.methods
+        .verifyByStake(new anchor.BN(0.5 * LAMPORTS_PER_SOL), fresh.publicKey)
+        .accounts({ agentIdentity: identityPda, authority: fresh.publicKey })
+        .signers([fresh])
+        .rpc();
+
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.isVerified).to.be.true;
+      expect(identity.verificationSource).to.deep.equal({ stake: {} });
+      const stake = await program.account.agentStake.fetch(stakePda(identityPda)[0]);
+      expect(stake.withdrawalAddress.toBase58()).to.equal(fresh.publicKey.toBase58());
+    });
+
+    it("an agent that is already verified cannot verify again", async () => {
+      try {
+        await program.methods
+          .verifyByStake(new anchor.BN(0.5 * LAMPORTS_PER_SOL), fresh.publicKey)
+          .accounts({ agentIdentity: agentPda(fresh.publicKey)[0], authority: fresh.publicKey })
+          .signers([fresh])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("AlreadyVerified");
+      }
+    });
+
+    it("a stake-backed verification cannot be renewed", async () => {
+      try {
+        await program.methods
+          .renewVerification()
+          .accounts({ agentIdentity: agentPda(fresh.publicKey)[0], authority: fresh.publicKey })
+          .signers([fresh])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("NotRenewable");
+      }
+    });
+
+    it("an existing stake keeps its withdrawal address", async () => {
+      try {
+        await program.methods
+          .verifyByStake(new anchor.BN(0.4 * LAMPORTS_PER_SOL), topUp.publicKey)
+          .accounts({ agentIdentity: agentPda(topUp.publicKey)[0], authority: topUp.publicKey })
+          .signers([topUp])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("InvalidWithdrawalAddress");
+      }
+    });
+
+    it("the top-up must reach the threshold together with the existing stake", async () => {
+      try {
+        await program.methods
+          .verifyByStake(new anchor.BN(0.3 * LAMPORTS_PER_SOL), payout)
+          .accounts({ agentIdentity: agentPda(topUp.publicKey)[0], authority: topUp.publicKey })
+          .signers([topUp])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("StakeTooLow");
+      }
+    });
+
+    it("tops up an existing stake to verify", async () => {
+      const [identityPda] = agentPda(topUp.publicKey);
+      const before = await program.account.agentStake.fetch(stakePda(identityPda)[0]);
+
+      await program.methods
+        .verifyByStake(new anchor.BN(0.45 * LAMPORTS_PER_SOL), payout)
+        .accounts({ agentIdentity: identityPda, authority: topUp.publicKey })
+        .signers([topUp])
+        .rpc();
+
+      const stake = await program.account.agentStake.fetch(stakePda(identityPda)[0]);
+      expect(stake.amount.toNumber()).to.equal(0.5 * LAMPORTS_PER_SOL);
+      expect(stake.stakedAt.toNumber()).to.equal(before.stakedAt.toNumber());
+      expect(stake.withdrawalAddress.toBase58()).to.equal(payout.toBase58());
+      // topping up does not clear the slash
+      expect(stake.isSlashed).to.be.true;
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.isVerified).to.be.true;
+      expect(identity.verificationSource).to.deep.equal({ stake: {} });
+      expect(identity.stakeAmount.toNumber()).to.equal(0.5 * LAMPORTS_PER_SOL);
+    });
+  });
+
+  // ==================== VERIFICATION EXPIRY ====================
+
+  describe("verification expiry and renewal", () => {
+    const renewer = Keypair.generate();
+    const unverified = Keypair.generate();
+    const PERIOD_SECS = 90 * 24 * 60 * 60;
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([renewer, unverified]);
+      await registerVerified(renewer, "https://example.com/renewer.json");
+      await program.methods
+        .registerAgent("https://example.com/unverified.json")
+        .accounts({ owner: unverified.publicKey })
+        .signers([unverified])
+        .rpc();
+    });
+
+    it("get_verified sets a verification period", async () => {
+      const identity = await program.account.agentIdentity.fetch(agentPda(renewer.publicKey)[0]);
+      expect(identity.isVerified).to.be.true;
+      expect(identity.verifiedUntil.toNumber() - identity.verifiedAt.toNumber()).to.equal(PERIOD_SECS);
+      expect(identity.verificationSource).to.deep.equal({ paid: {} });
+    });
+
+    it("renewing extends from the old deadline", async () => {
+      const [identityPda] = agentPda(renewer.publicKey);
+      const before = await program.account.agentIdentity.fetch(identityPda);
+
+      await program.methods
+        .renewVerification()
+        .accounts({ agentIdentity: identityPda, authority: renewer.publicKey })
+        .signers([renewer])
+        .rpc();
+
+      const after = await program.account.agentIdentity.fetch(identityPda);
+      expect(after.verifiedUntil.toNumber()).to.equal(before.verifiedUntil.toNumber() + PERIOD_SECS);
+      expect(after.verifiedAt.toNumber()).to.equal(before.verifiedAt.toNumber());
+    });
+
+    it("an active verification cannot be bought again", async () => {
+      try {
+        await program.methods
+          .getVerified()
+          .accounts({ agentIdentity: agentPda(renewer.publicKey)[0], authority: renewer.publicKey })
+          .signers([renewer])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("AlreadyVerified");
+      }
+    });
+
+    it("only the authority can renew", async () => {
+      try {
+        await program.methods
+          .renewVerification()
+          .accounts({ agentIdentity: agentPda(renewer.publicKey)[0], authority: randomWallet.publicKey })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("Unauthorized");
+      }
+    });
+
+    it("an unverified agent cannot renew", async () => {
+      try {
+        await program.methods
+          .renewVerification()
+          .accounts({ agentIdentity: agentPda(unverified.publicKey)[0], authority: unverified.publicKey })
+          .signers([unverified])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("VerificationExpired");
+      }
+    });
+
+    it("an active verification cannot be expired", async () => {
+      try {
+        await program.methods
+          .expireVerification()
+          .accounts({ agentIdentity: agentPda(renewer.publicKey)[0] })
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("VerificationStillActive");
+      }
+    });
+
+    it("an unverified agent cannot be expired", async () => {
+      try {
+        await program.methods
+          .expireVerification()
+          .accounts({ agentIdentity: agentPda(unverified.publicKey)[0] })
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("VerificationStillActive");
+      }
+    });
+  });
+
+  // ==================== VERIFICATION REVOCATION ====================
+
+  describe("verification revocation", () => {
+    const revoked = Keypair.generate();
+    const [treasuryPda] = PublicKey.findProgramAddressSync([Buffer.from("treasury")], program.programId);
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([revoked]);
+      await registerVerified(revoked, "https://example.com/revoked.json");
+    });
+
+    it("only the admin can revoke", async () => {
+      try {
+        await program.methods
+          .revokeVerification({ fraud: {} })
+          .accounts({ agentIdentity: agentPda(revoked.publicKey)[0], admin: randomWallet.publicKey })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("UnauthorizedAuthority");
+      }
+    });
+
+    it("revokes with a reason and counts the revocation", async () => {
+      const [identityPda] = agentPda(revoked.publicKey);
+
+      await program.methods
+        .revokeVerification({ fraud: {} })
+        .accounts({ agentIdentity: identityPda, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.isVerified).to.be.false;
+      expect(identity.verificationSource).to.be.null;
+      expect(identity.verificationRevocations).to.equal(1);
+    });
+
+    it("re-verifying after a revocation pays the multiplied fee", async () => {
+      const [identityPda] = agentPda(revoked.publicKey);
+      const before = await program.account.treasury.fetch(treasuryPda);
+
+      await program.methods
+        .getVerified()
+        .accounts({ agentIdentity: identityPda, authority: revoked.publicKey })
+        .signers([revoked])
+        .rpc();
+
+      const after = await program.account.treasury.fetch(treasuryPda);
+      // default multiplier is 2x the 0.01 SOL fee
+      expect(after.totalCollected.toNumber() - before.totalCollected.toNumber()).to.equal(0.02 * LAMPORTS_PER_SOL);
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.verificationSource).to.deep.equal({ paid: {} });
+    });
+
+    it("a partial slash below the stake threshold counts as a revocation", async () => {
+      const staked = Keypair.generate();
+      await airdrop([staked]);
+      await program.methods
+        .registerAgent("https://example.com/slashed-staker.json")
+        .accounts({ owner: staked.publicKey })
+        .signers([staked])
+        .rpc();
+      const [identityPda] = agentPda(staked.publicKey);
+      await program.methods
+        .verifyByStake(new anchor.BN(0.5 * LAMPORTS_PER_SOL), staked.publicKey)
+        .accounts({ agentIdentity: identityPda, authority: staked.publicKey })
+        .signers([staked])
+        .rpc();
+
+      await program.methods
+        .slashAgent(2_000)
+        .accounts({ agentIdentity: identityPda, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.stakeAmount.toNumber()).to.equal(0.4 * LAMPORTS_PER_SOL);
+      expect(identity.isVerified).to.be.false;
+      expect(identity.verificationSource).to.be.null;
+      expect(identity.verificationRevocations).to.equal(1);
+    });
+  });
+
+  // ==================== SPONSORS ====================
+
+  describe("sponsors: approval and quotas", () => {
+    const partner = Keypair.generate();
+    const outsider = Keypair.generate();
+    const onboardedA = Keypair.generate();
+    const onboardedB = Keypair.generate();
+
+    function sponsorPda(wallet: PublicKey): [PublicKey, number] {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("sponsor"), wallet.toBuffer()],
+        program.programId
+      );
+    }
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([partner, outsider], 10);
+    });
+
+    it("only the admin can approve a sponsor", async () => {
+      const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 86_400);
+
+      try {
+        await program.methods
+          .approveSponsor("outsider", 5, 5, expiresAt)
+          .accounts({ sponsorWallet: outsider.publicKey, admin: outsider.publicKey })
+          .signers([outsider])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("UnauthorizedAuthority");
+      }
+    });
+
+    it("approves a sponsor with quotas", async () => {
+      const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 86_400);
+
+      await program.methods
+        .approveSponsor("partner", 1, 1, expiresAt)
+        .accounts({ sponsorWallet: partner.publicKey, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+      const sponsor = await program.account.sponsor.fetch(sponsorPda(partner.publicKey)[0]);
+      expect(sponsor.wallet.toBase58()).to.equal(partner.publicKey.toBase58());
+      expect(sponsor.label).to.equal("partner");
+      expect(sponsor.registrationQuota).to.equal(1);
+      expect(sponsor.registrationsUsed).to.equal(0);
+      expect(sponsor.verificationQuota).to.equal(1);
+    });
+
+    it("an unapproved wallet cannot sponsor a registration", async () => {
+      try {
+        await program.methods
+          .sponsorRegister("https://example.com/unsponsored.json")
+          .accountsPartial({ agentWallet: Keypair.generate().publicKey, sponsor: null, authority: outsider.publicKey })
+          .signers([outsider])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("NotApprovedSponsor");
+      }
+    });
+
+    it("a sponsored registration uses quota and waits for the agent to claim it", async () => {
+      await program.methods
+        .sponsorRegister("https://example.com/onboarded-a.json")
+        .accountsPartial({ agentWallet: onboardedA.publicKey, sponsor: sponsorPda(partner.publicKey)[0], authority: partner.publicKey })
+        .signers([partner])
+        .rpc();
+
+      const identity = await program.account.agentIdentity.fetch(agentPda(onboardedA.publicKey)[0]);
+      expect(identity.owner.toBase58()).to.equal(onboardedA.publicKey.toBase58());
+      expect(identity.sponsor.toBase58()).to.equal(partner.publicKey.toBase58());
+      expect(identity.pendingClaim).to.be.true;
+
+      const sponsor = await program.account.sponsor.fetch(sponsorPda(partner.publicKey)[0]);
+      expect(sponsor.registrationsUsed).to.equal(1);
+    });
+
+    it("rejects registrations past the quota", async () => {
+      try {
+        await program.methods
+          .sponsorRegister("https://example.com/onboarded-b.json")
+          .accountsPartial({ agentWallet: onboardedB.publicKey, sponsor: sponsorPda(partner.publicKey)[0], authority: partner.publicKey })
+          .signers([partner])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("SponsorQuotaExceeded");
+      }
+    });
+
+    it("a sponsor cannot verify an identity it did not register", async () => {
+      try {
+        await program.methods
+          .sponsorVerify()
+          .accountsPartial({ agentIdentity: agentPda(owner.publicKey)[0], sponsor: sponsorPda(partner.publicKey)[0], authority: partner.publicKey })
+          .signers([partner])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("NotIdentitySponsor");
+      }
+    });
+
+    it("an unclaimed identity cannot be sponsor-verified", async () => {
+      try {
+        await program.methods
+          .sponsorVerify()
+          .accountsPartial({ agentIdentity: agentPda(onboardedA.publicKey)[0], sponsor: sponsorPda(partner.publicKey)[0], authority: partner.publicKey })
+          .signers([partner])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("ClaimPending");
+      }
+    });
+
+    it("sponsor_verify uses the verification quota", async () => {
+      const [identityPda] = agentPda(onboardedA.publicKey);
+
+      await program.methods
+        .acceptSponsoredIdentity(null)
+        .accounts({ agentWallet: onboardedA.publicKey })
+        .signers([onboardedA])
+        .rpc();
+      await program.methods
+        .sponsorVerify()
+        .accountsPartial({ agentIdentity: identityPda, sponsor: sponsorPda(partner.publicKey)[0], authority: partner.publicKey })
+        .signers([partner])
+        .rpc();
+
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.isVerified).to.be.true;
+      expect(identity.verificationSource).to.deep.equal({ sponsored: {} });
+      const sponsor = await program.account.sponsor.fetch(sponsorPda(partner.publicKey)[0]);
+      expect(sponsor.verificationsUsed).to.equal(1);
+
+      try {
+        await program.methods
+          .sponsorVerify()
+          .accountsPartial({ agentIdentity: identityPda, sponsor: sponsorPda(partner.publicKey)[0], authority: partner.publicKey })
+          .signers([partner])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("SponsorQuotaExceeded");
+      }
+    });
+
+    it("update_sponsor rejects an expiry in the past", async () => {
+      try {
+        await program.methods
+          .updateSponsor(2, 1, new anchor.BN(Math.floor(Date.now() / 1000) - 60))
+          .accounts({ sponsor: sponsorPda(partner.publicKey)[0], admin: admin.publicKey })
+          .signers([admin])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("SponsorExpired");
+      }
+    });
+
+    it("update_sponsor raises the quota without resetting usage", async () => {
+      const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 86_400);
+
+      await program.methods
+        .updateSponsor(2, 1, expiresAt)
+        .accounts({ sponsor: sponsorPda(partner.publicKey)[0], admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+      const sponsor = await program.account.sponsor.fetch(sponsorPda(partner.publicKey)[0]);
+      expect(sponsor.registrationQuota).to.equal(2);
+      expect(sponsor.registrationsUsed).to.equal(1);
+
+      await program.methods
+        .sponsorRegister("https://example.com/onboarded-b.json")
+        .accountsPartial({ agentWallet: onboardedB.publicKey, sponsor: sponsorPda(partner.publicKey)[0], authority: partner.publicKey })
+        .signers([partner])
+        .rpc();
+    });
+
+    it("a revoked sponsor can no longer register", async () => {
+      await program.methods
+        .revokeSponsor()
+        .accounts({ sponsor: sponsorPda(partner.publicKey)[0], admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+      const info = await provider.connection.getAccountInfo(sponsorPda(partner.publicKey)[0]);
+      expect(info).to.be.null;
+
+      try {
+        await program.methods
+          .sponsorRegister("https://example.com/after-revoke.json")
+          .accountsPartial({ agentWallet: Keypair.generate().publicKey, sponsor: null, authority: partner.publicKey })
+          .signers([partner])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("NotApprovedSponsor");
+      }
+    });
+  });
+
+  describe("sponsors: claiming a sponsored identity", () => {
+    const accepter = Keypair.generate();
+    const rejecter = Keypair.generate();
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([accepter, rejecter]);
+      // the treasury authority sponsors without a Sponsor account or quota
+      for (const kp of [accepter, rejecter]) {
+        await program.methods
+          .sponsorRegister("https://example.com/sponsored.json")
+          .accountsPartial({ agentWallet: kp.publicKey, sponsor: null, authority: admin.publicKey })
+          .signers([admin])
+          .rpc();
+      }
+    });
+
+    it("only the agent wallet can accept", async () => {
+      try {
+        await program.methods
+          .acceptSponsoredIdentity(null)
+          .accountsPartial({ agentIdentity: agentPda(accepter.publicKey)[0], agentWallet: randomWallet.publicKey })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("ConstraintSeeds");
+      }
+    });
+
+    it("accepts a sponsored identity with new metadata", async () => {
+      const [identityPda] = agentPda(accepter.publicKey);
+
+      await program.methods
+        .acceptSponsoredIdentity("https://example.com/claimed.json")
+        .accounts({ agentWallet: accepter.publicKey })
+        .signers([accepter])
+        .rpc();
+
+      const identity = await program.account.agentIdentity.fetch(identityPda);
+      expect(identity.pendingClaim).to.be.false;
+      expect(identity.metadataUri).to.equal("https://example.com/claimed.json");
+      expect(identity.sponsor.toBase58()).to.equal(admin.publicKey.toBase58());
+    });
+
+    it("cannot accept twice", async () => {
+      try {
+        await program.methods
+          .acceptSponsoredIdentity(null)
+          .accounts({ agentWallet: accepter.publicKey })
+          .signers([accepter])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("NotPendingClaim");
+      }
+    });
+
+    it("an accepted identity can no longer be rejected", async () => {
+      try {
+        await program.methods
+          .rejectSponsoredIdentity()
+          .accounts({ agentWallet: accepter.publicKey, sponsorWallet: admin.publicKey })
+          .signers([accepter])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("NotPendingClaim");
+      }
+    });
+
+    it("rent only goes back to the original sponsor", async () => {
+      try {
+        await program.methods
+          .rejectSponsoredIdentity()
+          .accounts({ agentWallet: rejecter.publicKey, sponsorWallet: randomWallet.publicKey })
+          .signers([rejecter])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("NotApprovedSponsor");
+      }
+    });
+
+    it("rejecting closes the identity back to the sponsor", async () => {
+      const [identityPda] = agentPda(rejecter.publicKey);
+      const before = await provider.connection.getBalance(admin.publicKey);
+
+      await program.methods
+        .rejectSponsoredIdentity()
+        .accounts({ agentWallet: rejecter.publicKey, sponsorWallet: admin.publicKey })
+        .signers([rejecter])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(identityPda)).to.be.null;
+      expect(await provider.connection.getBalance(admin.publicKey)).to.be.greaterThan(before);
+    });
+
+    it("the wallet can register its own identity after rejecting", async () => {
+      await program.methods
+        .registerAgent("https://example.com/self-registered.json")
+        .accounts({ owner: rejecter.publicKey })
+        .signers([rejecter])
+        .rpc();
+
+      const identity = await program.account.agentIdentity.fetch(agentPda(rejecter.publicKey)[0]);
+      expect(identity.sponsor).to.be.null;
+      expect(identity.pendingClaim).to.be.false;
+    });
+  });
+
+  // ==================== ATTESTATIONS ====================
+
+  describe("attestations", () => {
+    const attesterKp = Keypair.generate();
+    const subject = Keypair.generate();
+    const schemaId = crypto.randomBytes(32);
+    const unlistedSchemaId = crypto.randomBytes(32);
+
+    function attesterPda(wallet: PublicKey): [PublicKey, number] {
+      return PublicKey.findProgramAddressSync([Buffer.from("attester"), wallet.toBuffer()], program.programId);
+    }
+
+    function schemaPda(id: Buffer): [PublicKey, number] {
+      return PublicKey.findProgramAddressSync([Buffer.from("schema"), id], program.programId);
+    }
+
+    function attestationPda(agentId: PublicKey, attester: PublicKey, id: Buffer): [PublicKey, number] {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("attestation"), agentId.toBuffer(), attester.toBuffer(), id],
+        program.programId
+      );
+    }
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([attesterKp, subject]);
+      await program.methods
+        .registerAgent("https://example.com/attested.json")
+        .accounts({ owner: subject.publicKey })
+        .signers([subject])
+        .rpc();
+    });
+
+    it("only the admin can register attesters", async () => {
+      try {
+        await program.methods
+          .registerAttester("self-appointed", [Array.from(schemaId)])
+          .accounts({ attesterWallet: randomWallet.publicKey, admin: randomWallet.publicKey })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("UnauthorizedAuthority");
+      }
+    });
+
+    it("registers an attester and a revocable schema", async () => {
+      await program.methods
+        .registerAttester("kyc-provider", [Array.from(schemaId)])
+        .accounts({ attesterWallet: attesterKp.publicKey, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .registerSchema(Array.from(schemaId), "kyc(level:u8,country:string)", 2, 64, true, { allowedAttesters: {} })
+        .accounts({ creator: attesterKp.publicKey, admin: admin.publicKey })
+        .signers([attesterKp, admin])
+        .rpc();
+      await program.methods
+        .registerSchema(Array.from(unlistedSchemaId), "unlisted(flag:u8)", 1, 8, true, { allowedAttesters: {} })
+        .accounts({ creator: attesterKp.publicKey, admin: admin.publicKey })
+        .signers([attesterKp, admin])
+        .rpc();
+
+      const attester = await program.account.attester.fetch(attesterPda(attesterKp.publicKey)[0]);
+      expect(attester.schemas.length).to.equal(1);
+      const schema = await program.account.schema.fetch(schemaPda(schemaId)[0]);
+      expect(schema.creator.toBase58()).to.equal(attesterKp.publicKey.toBase58());
+      expect(schema.fieldCount).to.equal(2);
+    });
+
+    it("rejects data that does not match the schema", async () => {
+      try {
+        await program.methods
+          .issueAttestation(Array.from(schemaId), [Buffer.from([2])], "https://example.com/kyc.json", null)
+          .accounts({ agentIdentity: agentPda(subject.publicKey)[0], authority: attesterKp.publicKey })
+          .signers([attesterKp])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("SchemaFieldCountMismatch");
+      }
+    });
+
+    it("rejects schemas the attester is not allowed to issue", async () => {
+      try {
+        await program.methods
+          .issueAttestation(Array.from(unlistedSchemaId), [Buffer.from([1])], "https://example.com/unlisted.json", null)
+          .accounts({ agentIdentity: agentPda(subject.publicKey)[0], authority: attesterKp.publicKey })
+          .signers([attesterKp])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("SchemaNotAllowed");
+      }
+    });
+
+    it("issues an attestation", async () => {
+      const [identityPda] = agentPda(subject.publicKey);
+      const fields = [Buffer.from([2]), Buffer.from("DE")];
+
+      await program.methods
+        .issueAttestation(Array.from(schemaId), fields, "https://example.com/kyc.json", null)
+        .accounts({ agentIdentity: identityPda, authority: attesterKp.publicKey })
+        .signers([attesterKp])
+        .rpc();
+
+      const attestation = await program.account.attestation.fetch(attestationPda(identityPda, attesterKp.publicKey, schemaId)[0]);
+      expect(attestation.agentId.toBase58()).to.equal(identityPda.toBase58());
+      expect(attestation.dataHash).to.deep.equal(dataHash(fields));
+      expect(attestation.revoked).to.be.false;
+    });
+
+    it("the attester updates the data", async () => {
+      const [identityPda] = agentPda(subject.publicKey);
+      const [attestationAddr] = attestationPda(identityPda, attesterKp.publicKey, schemaId);
+      const fields = [Buffer.from([3]), Buffer.from("DE")];
+      const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 86_400);
+
+      await program.methods
+        .updateAttestation(fields, "https://example.com/kyc-v2.json", expiresAt)
+        .accountsPartial({ attestation: attestationAddr, schema: schemaPda(schemaId)[0], authority: attesterKp.publicKey })
+        .signers([attesterKp])
+        .rpc();
+
+      const attestation = await program.account.attestation.fetch(attestationAddr);
+      expect(attestation.dataHash).to.deep.equal(dataHash(fields));
+      expect(attestation.uri).to.equal("https://example.com/kyc-v2.json");
+      expect(attestation.expiresAt.toNumber()).to.equal(expiresAt.toNumber());
+    });
+
+    it("only the issuing attester can revoke", async () => {
+      const [attestationAddr] = attestationPda(agentPda(subject.publicKey)[0], attesterKp.publicKey, schemaId);
+
+      try {
+        await program.methods
+          .revokeAttestation()
+          .accountsPartial({ attestation: attestationAddr, schema: schemaPda(schemaId)[0], authority: randomWallet.publicKey })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("ConstraintSeeds");
+      }
+    });
+
+    it("revokes the attestation and blocks further updates", async () => {
+      const [attestationAddr] = attestationPda(agentPda(subject.publicKey)[0], attesterKp.publicKey, schemaId);
+
+      await program.methods
+        .revokeAttestation()
+        .accountsPartial({ attestation: attestationAddr, schema: schemaPda(schemaId)[0], authority: attesterKp.publicKey })
+        .signers([attesterKp])
+        .rpc();
+
+      const attestation = await program.account.attestation.fetch(attestationAddr);
+      expect(attestation.revoked).to.be.true;
+      expect(attestation.revokedAt).to.not.be.null;
+
+      try {
+        await program.methods
+          .updateAttestation([Buffer.from([4]), Buffer.from("DE")], "https://example.com/kyc-v3.json", null)
+          .accountsPartial({ attestation: attestationAddr, schema: schemaPda(schemaId)[0], authority: attesterKp.publicKey })
+          .signers([attesterKp])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("AttestationRevoked");
+      }
+    });
+
+    it("a schema id cannot be registered without the admin", async () => {
+      try {
+        await program.methods
+          .registerSchema(Array.from(crypto.randomBytes(32)), "squat(flag:u8)", 1, 8, true, { allowedAttesters: {} })
+          .accounts({ creator: randomWallet.publicKey, admin: randomWallet.publicKey })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("UnauthorizedAuthority");
+      }
+    });
+
+    it("a revoked attestation can be reissued, but not a live one", async () => {
+      const [identityPda] = agentPda(subject.publicKey);
+      const fields = [Buffer.from([1]), Buffer.from("FR")];
+      await program.methods
+        .issueAttestation(Array.from(schemaId), fields, "https://example.com/kyc-v3.json", null)
+        .accounts({ agentIdentity: identityPda, authority: attesterKp.publicKey })
+        .signers([attesterKp])
+        .rpc();
+
+      const attestation = await program.account.attestation.fetch(attestationPda(identityPda, attesterKp.publicKey, schemaId)[0]);
+      expect(attestation.revoked).to.be.false;
+      expect(attestation.revokedAt).to.be.null;
+      expect(attestation.dataHash).to.deep.equal(dataHash(fields));
+
+      try {
+        await program.methods
+          .issueAttestation(Array.from(schemaId), fields, "https://example.com/kyc-v4.json", null)
+          .accounts({ agentIdentity: identityPda, authority: attesterKp.publicKey })
+          .signers([attesterKp])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("AttestationExists");
+      }
+    });
+
+    it("a deregistered attester can no longer issue", async () => {
+      await program.methods
+        .revokeAttester()
+        .accounts({ attester: attesterPda(attesterKp.publicKey)[0], admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+      try {
+        await program.methods
+          .issueAttestation(Array.from(schemaId), [Buffer.from([1]), Buffer.from("FR")], "https://example.com/kyc.json", null)
+          .accounts({ agentIdentity: agentPda(subject.publicKey)[0], authority: attesterKp.publicKey })
+          .signers([attesterKp])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("AccountNotInitialized");
+      }
+    });
+  });
+
+  describe("build attestations", () => {
+    const builder = Keypair.generate();
+    const auditor = Keypair.generate();
+    const buildSchemaId = crypto.randomBytes(32);
+    const otherSchemaId = crypto.randomBytes(32);
+    const image = crypto.randomBytes(32);
+
+    function buildPda(agentId: PublicKey, version: number): [PublicKey, number] {
+      const v = Buffer.alloc(4);
+      v.writeUInt32LE(version);
+      return PublicKey.findProgramAddressSync([Buffer.from("build"), agentId.toBuffer(), v], program.programId);
+    }
+
+    function attestationPda(agentId: PublicKey, schemaId: Buffer): PublicKey {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("attestation"), agentId.toBuffer(), auditor.publicKey.toBuffer(), schemaId],
+        program.programId
+      )[0];
+    }
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([builder, auditor]);
+      const [identityPda] = agentPda(builder.publicKey);
+      await program.methods
+        .registerAgent("https://example.com/builder.json")
+        .accounts({ owner: builder.publicKey })
+        .signers([builder])
+        .rpc();
+      await program.methods
+        .registerAttester("build-auditor", [Array.from(buildSchemaId), Array.from(otherSchemaId)])
+        .accounts({ attesterWallet: auditor.publicKey, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+      for (const id of [buildSchemaId, otherSchemaId]) {
+        await program.methods
+          .registerSchema(Array.from(id), "build(image:bytes32)", 1, 32, true, { allowedAttesters: {} })
+          .accounts({ creator: auditor.publicKey, admin: admin.publicKey })
+          .signers([auditor, admin])
+          .rpc();
+        await program.methods
+          .issueAttestation(Array.from(id), [image], "https://example.com/build-audit.json", null)
+          .accounts({ agentIdentity: identityPda, authority: auditor.publicKey })
+          .signers([auditor])
+          .rpc();
+      }
+      await program.methods
+        .setBuildSchema(Array.from(buildSchemaId))
+        .accounts({ authority: admin.publicKey })
+        .signers([admin])
+        .rpc();
+      // version 0 matches the attested image, version 1 does not
+      for (const codeHash of [dataHash([image]), Array.from(crypto.randomBytes(32))]) {
+        await program.methods
+          .declareBuild("eliza", "llama-3-70b", codeHash, null)
+          .accounts({ agentIdentity: identityPda, authority: builder.publicKey })
+          .signers([builder])
+          .rpc();
+      }
+    });
+
+    it("rejects attestations under another schema", async () => {
+      const [identityPda] = agentPda(builder.publicKey);
+      try {
+        await program.methods
+          .attachBuildAttestation()
+          .accounts({ agentIdentity: identityPda, agentBuild: buildPda(identityPda, 0)[0], attestation: attestationPda(identityPda, otherSchemaId), authority: builder.publicKey })
+          .signers([builder])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("NotBuildSchema");
+      }
+    });
+
+    it("rejects an attestation for a different code hash", async () => {
+      const [identityPda] = agentPda(builder.publicKey);
+      try {
+        await program.methods
+          .attachBuildAttestation()
+          .accounts({ agentIdentity: identityPda, agentBuild: buildPda(identityPda, 1)[0], attestation: attestationPda(identityPda, buildSchemaId), authority: builder.publicKey })
+          .signers([builder])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("BuildCodeHashMismatch");
+      }
+    });
+
+    it("attaches a matching build attestation", async () => {
+      const [identityPda] = agentPda(builder.publicKey);
+      const attestation = attestationPda(identityPda, buildSchemaId);
+
+      await program.methods
+        .attachBuildAttestation()
+        .accounts({ agentIdentity: identityPda, agentBuild: buildPda(identityPda, 0)[0], attestation, authority: builder.publicKey })
+        .signers([builder])
+        .rpc();
+
+      const build = await program.account.agentBuild.fetch(buildPda(identityPda, 0)[0]);
+      expect(build.attestation.toBase58()).to.equal(attestation.toBase58());
+    });
+
+    it("rejects a revoked attestation", async () => {
+      const [identityPda] = agentPda(builder.publicKey);
+      const attestation = attestationPda(identityPda, buildSchemaId);
+      await program.methods
+        .revokeAttestation()
+        .accountsPartial({ attestation, schema: PublicKey.findProgramAddressSync([Buffer.from("schema"), buildSchemaId], program.programId)[0], authority: auditor.publicKey })
+        .signers([auditor])
+        .rpc();
+
+      try {
+        await program.methods
+          .attachBuildAttestation()
+          .accounts({ agentIdentity: identityPda, agentBuild: buildPda(identityPda, 0)[0], attestation, authority: builder.publicKey })
+          .signers([builder])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("AttestationRevoked");
+      }
+    });
+  });
+
+  // ==================== FEEDBACK & REPUTATION ====================
+
+  describe("submit_feedback", () => {
+    it("submits positive feedback", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const [repPda] = reputationPda(identityPda);
+
+      await program.methods
+        .submitFeedback(true, "good trade on torch.market", null)
+        .accounts({
+          agentIdentity: identityPda,
+          reviewer: randomWallet.publicKey,
+        })
+        .signers([randomWallet])
+        .rpc();
+
+      const rep = await program.account.agentReputation.fetch(repPda);
+      expect(rep.totalInteractions.toNumber()).to.equal(1);
+      expect(rep.positiveFeedback.toNumber()).to.equal(1);
+      expect(rep.negativeFeedback.toNumber()).to.equal(0);
+      expect(rep.reputationScore).to.equal(10000); // 100%
+      // one review barely moves the confidence score off the 50% prior
+      expect(rep.confidenceScore).to.be.above(5000).and.below(7000);
+    });
+
+    it("submits negative feedback, score updates", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const [repPda] = reputationPda(identityPda);
+
+      await program.methods
+        .submitFeedback(false, "failed to deliver", null)
+        .accounts({
+          agentIdentity: identityPda,
+          reviewer: walletD.publicKey,
+        })
+        .signers([walletD])
+        .rpc();
+
+      const rep = await program.account.agentReputation.fetch(repPda);
+      expect(rep.totalInteractions.toNumber()).to.equal(2);
+      expect(rep.positiveFeedback.toNumber()).to.equal(1);
+      expect(rep.negativeFeedback.toNumber()).to.equal(1);
+      expect(rep.reputationScore).to.equal(5000); // 50%
+    });
+
+    it("re-submitting replaces the reviewer's verdict instead of adding one", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const [repPda] = reputationPda(identityPda);
+
+      await program.methods
+        .submitFeedback(false, "changed my mind", null)
+        .accounts({
+          agentIdentity: identityPda,
+          reviewer: randomWallet.publicKey,
+        })
+        .signers([randomWallet])
+        .rpc();
+
+      const rep = await program.account.agentReputation.fetch(repPda);
+      expect(rep.totalInteractions.toNumber()).to.equal(2);
+      expect(rep.positiveFeedback.toNumber()).to.equal(0);
+      expect(rep.negativeFeedback.toNumber()).to.equal(2);
+      expect(rep.reputationScore).to.equal(0);
+    });
+
+    it("withdraws feedback and closes the record", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const [repPda] = reputationPda(identityPda);
+      const [recordPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("feedback"), identityPda.toBuffer(), walletD.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .withdrawFeedback()
+        .accounts({
+          agentReputation: repPda,
+          feedbackRecord: recordPda,
+          reviewer: walletD.publicKey,
+        })
+        .signers([walletD])
+        .rpc();
+
+      const rep = await program.account.agentReputation.fetch(repPda);
+      expect(rep.totalInteractions.toNumber()).to.equal(1);
+      expect(rep.negativeFeedback.toNumber()).to.equal(1);
+      expect(await provider.connection.getAccountInfo(recordPda)).to.be.null;
+    });
+
+    it("submits per-dimension ratings", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const [ratingsPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("ratings"), identityPda.toBuffer()],
+        program.programId
+      );
+
+      // quality 5, reliability 4, speed not rated, honesty 3
+      await program.methods
+        .submitRating([5, 4, 0, 3], "solid work", null)
+        .accounts({
+          feedback: { agentIdentity: identityPda, reviewer: walletD.publicKey },
+          payer: walletD.publicKey,
+        })
+        .signers([walletD])
+        .rpc();
+
+      const totals = await program.account.agentRatings.fetch(ratingsPda);
+      expect(totals.sums.map((n: any) => n.toNumber())).to.deep.equal([5, 4, 0, 3]);
+      expect(totals.counts.map((n: any) => n.toNumber())).to.deep.equal([1, 1, 0, 1]);
+      const rep = await program.account.agentReputation.fetch(reputationPda(identityPda)[0]);
+      expect(rep.positiveFeedback.toNumber()).to.equal(1); // mean 4 counts as a thumbs-up
+    });
+
+    it("get_reputation decays the stored score without writing", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const [repPda] = reputationPda(identityPda);
+      const before = await program.account.agentReputation.fetch(repPda);
+
+      const view = await program.methods
+        .getReputation()
+        .accounts({ agentReputation: repPda })
+        .view();
+
+      expect(view.score).to.equal(before.reputationScore);
+      // seconds since the last feedback against a six-month half-life: barely decayed
+      expect(view.decayedScore).to.be.at.most(before.reputationScore);
+      expect(view.decayedScore).to.be.at.least(before.reputationScore - 1);
+      expect(view.decayedPositive.toNumber()).to.be.at.most(before.decayedPositive.toNumber());
+      expect(view.decayedPositive.toNumber()).to.be.at.least(before.decayedPositive.toNumber() * 0.999);
+      expect(view.decayedNegative.toNumber()).to.be.at.most(before.decayedNegative.toNumber());
+      expect(view.confidenceScore).to.be.within(before.confidenceScore - 1, before.confidenceScore + 1);
+
+      const after = await program.account.agentReputation.fetch(repPda);
+      expect(after.decayedPositive.toNumber()).to.equal(before.decayedPositive.toNumber());
+      expect(after.lastDecayTs.toNumber()).to.equal(before.lastDecayTs.toNumber());
+    });
+
+    it("snapshots reputation at most once per interval", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const [repPda] = reputationPda(identityPda);
+      const [historyPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("reputation_history"), identityPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .snapshotReputation()
+        .accounts({ agentReputation: repPda, payer: randomWallet.publicKey })
+        .signers([randomWallet])
+        .rpc();
+
+      const history = await program.account.reputationHistory.fetch(historyPda);
+      const rep = await program.account.agentReputation.fetch(repPda);
+      expect(history.count).to.equal(1);
+      expect(history.snapshots[0].positiveFeedback.toNumber()).to.equal(rep.positiveFeedback.toNumber());
+      expect(history.snapshots[0].negativeFeedback.toNumber()).to.equal(rep.negativeFeedback.toNumber());
+
+      try {
+        await program.methods
+          .snapshotReputation()
+          .accounts({ agentReputation: repPda, payer: randomWallet.publicKey })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("SnapshotTooSoon");
+      }
+    });
+  });
+
+  describe("reviewer-weighted feedback", () => {
+    const target = Keypair.generate();
+    const anonymous = Keypair.generate();
+    const verifiedReviewer = Keypair.generate();
+    const stakedReviewer = Keypair.generate();
+    const noOptional = { interactionEvidence: null, category: null, categoryReputation: null };
+
+    function feedbackPda(agentId: PublicKey, reviewer: PublicKey): PublicKey {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("feedback"), agentId.toBuffer(), reviewer.toBuffer()],
+        program.programId
+      )[0];
+    }
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([target, anonymous, verifiedReviewer, stakedReviewer]);
+      await program.methods
+        .registerAgent("https://example.com/weighted-target.json")
+        .accounts({ owner: target.publicKey })
+        .signers([target])
+        .rpc();
+      await registerVerified(verifiedReviewer, "https://example.com/verified-reviewer.json");
+      await registerVerified(stakedReviewer, "https://example.com/staked-reviewer.json");
+      await program.methods
+        .stake(new anchor.BN(0.1 * LAMPORTS_PER_SOL), stakedReviewer.publicKey)
+        .accounts({ agentIdentity: agentPda(stakedReviewer.publicKey)[0], authority: stakedReviewer.publicKey })
+        .signers([stakedReviewer])
+        .rpc();
+    });
+
+    it("an anonymous wallet gets the base weight", async () => {
+      const [identityPda] = agentPda(target.publicKey);
+
+      await program.methods
+        .submitFeedback(false, "never answered", null)
+        .accountsPartial({ agentIdentity: identityPda, reviewerIdentity: null, reviewerStake: null, ...noOptional, reviewer: anonymous.publicKey })
+        .signers([anonymous])
+        .rpc();
+
+      const record = await program.account.feedbackRecord.fetch(feedbackPda(identityPda, anonymous.publicKey));
+      expect(record.weight).to.equal(100);
+    });
+
+    it("a verified reviewer adds a tenth of its verification points", async () => {
+      const [identityPda] = agentPda(target.publicKey);
+
+      await program.methods
+        .submitFeedback(true, "delivered", null)
+        .accountsPartial({
+          agentIdentity: identityPda,
+          reviewerIdentity: agentPda(verifiedReviewer.publicKey)[0],
+          reviewerStake: null,
+          ...noOptional,
+          reviewer: verifiedReviewer.publicKey,
+        })
+        .signers([verifiedReviewer])
+        .rpc();
+
+      const record = await program.account.feedbackRecord.fetch(feedbackPda(identityPda, verifiedReviewer.publicKey));
+      expect(record.weight).to.equal(200); // base 100 + Verified 1000 / 10
+    });
+
+    it("a stake must belong to the reviewer's identity", async () => {
+      try {
+        await program.methods
+          .submitFeedback(true, "borrowed stake", null)
+          .accountsPartial({
+            agentIdentity: agentPda(target.publicKey)[0],
+            reviewerIdentity: agentPda(verifiedReviewer.publicKey)[0],
+            reviewerStake: stakePda(agentPda(stakedReviewer.publicKey)[0])[0],
+            ...noOptional,
+            reviewer: verifiedReviewer.publicKey,
+          })
+          .signers([verifiedReviewer])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("ReviewerStakeMismatch");
+      }
+    });
+
+    it("a staked reviewer weighs in its tier and stake", async () => {
+      const [identityPda] = agentPda(target.publicKey);
+      const [stakerIdentity] = agentPda(stakedReviewer.publicKey);
+
+      await program.methods
+        .submitFeedback(true, "fast and correct", null)
+        .accountsPartial({
+          agentIdentity: identityPda,
+          reviewerIdentity: stakerIdentity,
+          reviewerStake: stakePda(stakerIdentity)[0],
+          ...noOptional,
+          reviewer: stakedReviewer.publicKey,
+        })
+        .signers([stakedReviewer])
+        .rpc();
+
+      const record = await program.account.feedbackRecord.fetch(feedbackPda(identityPda, stakedReviewer.publicKey));
+      expect(record.weight).to.equal(400); // base 100 + Secured 1500 / 10 + stake 1500 / 10
+
+      const rep = await program.account.agentReputation.fetch(reputationPda(identityPda)[0]);
+      expect(rep.weightedPositive.toNumber()).to.equal(600);
+      expect(rep.weightedNegative.toNumber()).to.equal(100);
+      // two positives against one negative, but weighted 600 : 100
+      expect(rep.reputationScore).to.be.within(8500, 8600);
+    });
+
+    it("the confidence prior needs a positive, bounded weight", async () => {
+      for (const weight of [0, 10_001]) {
+        try {
+          await program.methods
+            .setReputationPrior(5_000, weight)
+            .accounts({ authority: admin.publicKey })
+            .signers([admin])
+            .rpc();
+          expect.fail("should have thrown");
+        } catch (e: any) {
+          expect(e.toString()).to.contain("InvalidPriorWeight");
+        }
+      }
+    });
+  });
+
+  describe("feedback disputes", () => {
+    const disputed = Keypair.generate();
+    const harsh = Keypair.generate();
+    const fair = Keypair.generate();
+    const noOptional = { reviewerIdentity: null, reviewerStake: null, interactionEvidence: null, category: null, categoryReputation: null };
+
+    function feedbackPda(reviewer: PublicKey): PublicKey {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("feedback"), agentPda(disputed.publicKey)[0].toBuffer(), reviewer.toBuffer()],
+        program.programId
+      )[0];
+    }
+
+    function disputePda(reviewer: PublicKey): PublicKey {
+      return PublicKey.findProgramAddressSync([Buffer.from("dispute"), feedbackPda(reviewer).toBuffer()], program.programId)[0];
+    }
+
+    async function review(reviewer: Keypair, positive: boolean) {
+      await program.methods
+        .submitFeedback(positive, positive ? "fine after all" : "took my money", null)
+        .accountsPartial({ agentIdentity: agentPda(disputed.publicKey)[0], ...noOptional, reviewer: reviewer.publicKey })
+        .signers([reviewer])
+        .rpc();
+    }
+
+    async function withdraw(reviewer: Keypair) {
+      await program.methods
+        .withdrawFeedback()
+        .accounts({ agentReputation: reputationPda(agentPda(disputed.publicKey)[0])[0], feedbackRecord: feedbackPda(reviewer.publicKey), reviewer: reviewer.publicKey })
+        .signers([reviewer])
+        .rpc();
+    }
+
+    async function openDispute(reviewer: Keypair) {
+      await program.methods
+        .openFeedbackDispute("https://example.com/our-side.json")
+        .accounts({ agentIdentity: agentPda(disputed.publicKey)[0], feedbackRecord: feedbackPda(reviewer.publicKey), authority: disputed.publicKey })
+        .signers([disputed])
+        .rpc();
+    }
+
+    async function resolve(reviewer: Keypair, upheld: boolean, discountBps: number) {
+      await program.methods
+        .resolveFeedbackDispute(upheld, discountBps)
+        .accountsPartial({ feedbackRecord: feedbackPda(reviewer.publicKey), categoryReputation: null, resolver: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    }
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([disputed, harsh, fair]);
+      await program.methods
+        .registerAgent("https://example.com/disputed.json")
+        .accounts({ owner: disputed.publicKey })
+        .signers([disputed])
+        .rpc();
+      await review(harsh, false);
+      await review(fair, false);
+    });
+
+    it("feedback cannot be withdrawn while its dispute is open", async () => {
+      await openDispute(harsh);
+
+      try {
+        await withdraw(harsh);
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("FeedbackUnderDispute");
+      }
+    });
+
+    it("an upheld dispute discounts the feedback", async () => {
+      await resolve(harsh, true, 5000);
+
+      const record = await program.account.feedbackRecord.fetch(feedbackPda(harsh.publicKey));
+      expect(record.weight).to.equal(50);
+      expect(record.discountBps).to.equal(5000);
+      const dispute = await program.account.feedbackDispute.fetch(disputePda(harsh.publicKey));
+      expect(dispute.status).to.deep.equal({ upheld: {} });
+    });
+
+    it("an upheld ruling cannot be undone by withdrawing and resubmitting", async () => {
+      try {
+        await withdraw(harsh);
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("FeedbackUnderDispute");
+      }
+
+      // editing in place keeps the discount
+      await review(harsh, false);
+      const record = await program.account.feedbackRecord.fetch(feedbackPda(harsh.publicKey));
+      expect(record.weight).to.equal(50);
+    });
+
+    it("a rejected dispute lets the reviewer withdraw and resubmit", async () => {
+      await openDispute(fair);
+      await resolve(fair, false, 0);
+
+      try {
+        await openDispute(fair);
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("DisputeAlreadyResolved");
+      }
+
+      await withdraw(fair);
+      expect(await provider.connection.getAccountInfo(feedbackPda(fair.publicKey))).to.be.null;
+      // created_at must land after the ruling for the refiled verdict to be contestable
+      await new Promise((r) => setTimeout(r, 1500));
+      await review(fair, false);
+
+      const record = await program.account.feedbackRecord.fetch(feedbackPda(fair.publicKey));
+      expect(record.weight).to.equal(100);
+    });
+
+    it("a verdict refiled after a rejected dispute can be disputed again", async () => {
+      await openDispute(fair);
+
+      const dispute = await program.account.feedbackDispute.fetch(disputePda(fair.publicKey));
+      expect(dispute.status).to.deep.equal({ open: {} });
+      expect(dispute.resolvedAt).to.be.null;
+    });
+  });
+
+  describe("feedback categories", () => {
+    const specialist = Keypair.generate();
+    const trader = Keypair.generate();
+    const tradingId = 1000 + Math.floor(Math.random() * 50_000);
+    const supportId = tradingId + 1;
+
+    function categoryPda(id: number): PublicKey {
+      const le = Buffer.alloc(2);
+      le.writeUInt16LE(id);
+      return PublicKey.findProgramAddressSync([Buffer.from("category"), le], program.programId)[0];
+    }
+
+    function bucketPda(agentId: PublicKey, id: number): PublicKey {
+      const le = Buffer.alloc(2);
+      le.writeUInt16LE(id);
+      return PublicKey.findProgramAddressSync([Buffer.from("category_reputation"), agentId.toBuffer(), le], program.programId)[0];
+    }
+
+    function feedbackPda(): PublicKey {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("feedback"), agentPda(specialist.publicKey)[0].toBuffer(), trader.publicKey.toBuffer()],
+        program.programId
+      )[0];
+    }
+
+    async function review(positive: boolean, id: number | null) {
+      const [identityPda] = agentPda(specialist.publicKey);
+      await program.methods
+        .submitFeedback(positive, "category review", null)
+        .accountsPartial({
+          agentIdentity: identityPda,
+          reviewerIdentity: null,
+          reviewerStake: null,
+          interactionEvidence: null,
+          category: id === null ? null : categoryPda(id),
+          categoryReputation: id === null ? null : bucketPda(identityPda, id),
+          reviewer: trader.publicKey,
+        })
+        .signers([trader])
+        .rpc();
+    }
+
+    async function withdraw(id: number) {
+      const [identityPda] = agentPda(specialist.publicKey);
+      await program.methods
+        .withdrawFeedback()
+        .accountsPartial({
+          agentReputation: reputationPda(identityPda)[0],
+          feedbackRecord: feedbackPda(),
+          agentRatings: null,
+          categoryReputation: bucketPda(identityPda, id),
+          reviewer: trader.publicKey,
+        })
+        .signers([trader])
+        .rpc();
+    }
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([specialist, trader]);
+      await program.methods
+        .registerAgent("https://example.com/specialist.json")
+        .accounts({ owner: specialist.publicKey })
+        .signers([specialist])
+        .rpc();
+    });
+
+    it("only the admin registers categories", async () => {
+      try {
+        await program.methods
+          .registerCategory(tradingId, "trading")
+          .accounts({ admin: randomWallet.publicKey })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("UnauthorizedAuthority");
+      }
+    });
+
+    it("registers categories and opens the agent's buckets", async () => {
+      const [identityPda] = agentPda(specialist.publicKey);
+      for (const [id, name] of [[tradingId, "trading"], [supportId, "support"]] as [number, string][]) {
+        await program.methods
+          .registerCategory(id, name)
+          .accounts({ admin: admin.publicKey })
+          .signers([admin])
+          .rpc();
+        await program.methods
+          .openCategoryReputation()
+          .accounts({ agentIdentity: identityPda, category: categoryPda(id), payer: trader.publicKey })
+          .signers([trader])
+          .rpc();
+      }
+
+      const category = await program.account.category.fetch(categoryPda(tradingId));
+      expect(category.name).to.equal("trading");
+      expect(category.active).to.be.true;
+      const bucket = await program.account.agentReputation.fetch(bucketPda(identityPda, tradingId));
+      expect(bucket.agentId.toBase58()).to.equal(identityPda.toBase58());
+    });
+
+    it("feedback with a category also counts in its bucket", async () => {
+      const [identityPda] = agentPda(specialist.publicKey);
+      await review(true, tradingId);
+
+      const record = await program.account.feedbackRecord.fetch(feedbackPda());
+      expect(record.category).to.equal(tradingId);
+      const bucket = await program.account.agentReputation.fetch(bucketPda(identityPda, tradingId));
+      expect(bucket.totalInteractions.toNumber()).to.equal(1);
+      expect(bucket.positiveFeedback.toNumber()).to.equal(1);
+
+      const view = await program.methods
+        .getCategoryReputation()
+        .accounts({ category: categoryPda(tradingId), categoryReputation: bucketPda(identityPda, tradingId) })
+        .view();
+      expect(view.score).to.equal(10000);
+    });
+
+    it("a bucket for another category is rejected", async () => {
+      const [identityPda] = agentPda(specialist.publicKey);
+      try {
+        await program.methods
+          .submitFeedback(true, "wrong bucket", null)
+          .accountsPartial({
+            agentIdentity: identityPda,
+            reviewerIdentity: null,
+            reviewerStake: null,
+            interactionEvidence: null,
+            category: categoryPda(tradingId),
+            categoryReputation: bucketPda(identityPda, supportId),
+            reviewer: trader.publicKey,
+          })
+          .signers([trader])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("ConstraintSeeds");
+      }
+    });
+
+    it("edits still work after the category is deactivated", async () => {
+      const [identityPda] = agentPda(specialist.publicKey);
+      await program.methods
+        .setCategoryActive(false)
+        .accounts({ category: categoryPda(tradingId), admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+      await review(false, tradingId);
+
+      const bucket = await program.account.agentReputation.fetch(bucketPda(identityPda, tradingId));
+      expect(bucket.totalInteractions.toNumber()).to.equal(1);
+      expect(bucket.negativeFeedback.toNumber()).to.equal(1);
+      expect(bucket.positiveFeedback.toNumber()).to.equal(0);
+    });
+
+    it("an edit cannot move the verdict to another category", async () => {
+      for (const id of [supportId, null]) {
+        try {
+          await review(true, id);
+          expect.fail("should have thrown");
+        } catch (e: any) {
+          expect(e.toString()).to.contain("CategoryMismatch");
+        }
+      }
+    });
+
+    it("withdrawing closes the record and frees the reviewer to file under another category", async () => {
+      const [identityPda] = agentPda(specialist.publicKey);
+      await withdraw(tradingId);
+
+      expect(await provider.connection.getAccountInfo(feedbackPda())).to.be.null;
+      const trading = await program.account.agentReputation.fetch(bucketPda(identityPda, tradingId));
+      expect(trading.totalInteractions.toNumber()).to.equal(0);
+
+      await review(true, supportId);
+      const record = await program.account.feedbackRecord.fetch(feedbackPda());
+      expect(record.category).to.equal(supportId);
+      const support = await program.account.agentReputation.fetch(bucketPda(identityPda, supportId));
+      expect(support.totalInteractions.toNumber()).to.equal(1);
+    });
+
+    it("a deactivated category takes no new verdicts", async () => {
+      await withdraw(supportId);
+      try {
+        await review(true, tradingId);
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("CategoryInactive");
+      }
+    });
+  });
+
+  describe("external reputation", () => {
+    const rated = Keypair.generate();
+    const fan = Keypair.generate();
+    const oracleKey = Keypair.generate();
+    const impostor = Keypair.generate();
+
+    function oraclePda(signer: PublicKey): PublicKey {
+      return PublicKey.findProgramAddressSync([Buffer.from("oracle"), signer.toBuffer()], program.programId)[0];
+    }
+
+    function externalPda(signer: PublicKey): PublicKey {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("external_reputation"), agentPda(rated.publicKey)[0].toBuffer(), signer.toBuffer()],
+        program.programId
+      )[0];
+    }
+
+    // domain ‖ program id ‖ agent ‖ score_bps u16 ‖ observed_at i64 ‖ nonce u64, all little-endian
+    function scoreMessage(scoreBps: number, observedAt: number, nonce: number): Buffer {
+      const tail = Buffer.alloc(18);
+      tail.writeUInt16LE(scoreBps, 0);
+      tail.writeBigInt64LE(BigInt(observedAt), 2);
+      tail.writeBigUInt64LE(BigInt(nonce), 10);
+      return Buffer.concat([Buffer.from("SAID:external_reputation:v1"), program.programId.toBuffer(), agentPda(rated.publicKey)[0].toBuffer(), tail]);
+    }
+
+    async function chainNow(): Promise<number> {
+      return (await provider.connection.getBlockTime(await provider.connection.getSlot()))!;
+    }
+
+    async function relay(scoreBps: number, observedAt: number, nonce: number, signer = oracleKey) {
+      const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: scoreMessage(scoreBps, observedAt, nonce),
+      });
+      await program.methods
+        .updateExternalReputation(scoreBps, new anchor.BN(observedAt), new anchor.BN(nonce))
+        .accounts({
+          agentIdentity: agentPda(rated.publicKey)[0],
+          oracle: oraclePda(oracleKey.publicKey),
+          payer: fan.publicKey,
+          instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .preInstructions([ed25519Ix])
+        .signers([fan])
+        .rpc();
+    }
+
+    async function trust(externalReputation: PublicKey | null = externalPda(oracleKey.publicKey)) {
+      const [identityPda] = agentPda(rated.publicKey);
+      return program.methods
+        .computeTrustScore()
+        .accountsPartial({ agentIdentity: identityPda, agentStake: null, agentReputation: reputationPda(identityPda)[0], externalReputation })
+        .view();
+    }
+
+    before(async function () {
+      if (!admin) return skipWithoutAdmin(this);
+      await ensureProtocolAccounts(admin);
+      await airdrop([rated, fan]);
+      await program.methods
+        .registerAgent("https://example.com/rated.json")
+        .accounts({ owner: rated.publicKey })
+        .signers([rated])
+        .rpc();
+      // one positive review: SAID reputation at its 50-point maximum
+      await program.methods
+        .submitFeedback(true, "great", null)
+        .accountsPartial({ agentIdentity: agentPda(rated.publicKey)[0], reviewerIdentity: null, reviewerStake: null, interactionEvidence: null, category: null, categoryReputation: null, reviewer: fan.publicKey })
+        .signers([fan])
+        .rpc();
+      await program.methods
+        .registerOracle("fairscale", new anchor.BN(3600))
+        .accounts({ oracleSigner: oracleKey.publicKey, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+      await program.methods
+        .setReputationOracle(oracleKey.publicKey)
+        .accounts({ authority: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    });
+
+    after(async function () {
+      if (!admin) return;
+      await program.methods
+        .setReputationOracle(null)
+        .accounts({ authority: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    });
+
+    it("falls back to a neutral oracle score before any import", async () => {
+      const score = await trust();
+      expect(score.oracleFallback).to.be.true;
+      expect(score.reputation).to.be.within(3740, 3750); // 5000 / 2 + 5000 / 4
+    });
+
+    it("only the pinned oracle's account is accepted", async () => {
+      for (const account of [null, externalPda(impostor.publicKey)]) {
+        try {
+          await trust(account);
+          expect.fail("should have thrown");
+        } catch (e: any) {
+          // view() failures surface through the simulation logs
+          expect(e.toString() + (e.simulationResponse?.logs ?? e.logs ?? []).join("\n")).to.contain("ExternalReputationMismatch");
+        }
+      }
+    });
+
+    it("rejects a score signed by another key", async () => {
+      try {
+        await relay(9000, await chainNow(), 1, impostor);
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("InvalidEd25519Instruction");
+      }
+    });
+
+    it("a fresh low score pulls the blend below the fallback", async () => {
+      await relay(0, await chainNow(), 1);
+
+      const external = await program.account.externalReputation.fetch(externalPda(oracleKey.publicKey));
+      expect(external.scoreBps).to.equal(0);
+      expect(external.nonce.toNumber()).to.equal(1);
+      const score = await trust();
+      expect(score.oracleFallback).to.be.false;
+      expect(score.reputation).to.be.within(2490, 2500); // 5000 / 2 + 0 / 4
+    });
+
+    it("rejects a replayed nonce", async () => {
+      try {
+        await relay(9000, await chainNow(), 1);
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("InvalidOracleNonce");
+      }
+    });
+
+    it("rejects an observed_at past the staleness limit", async () => {
+      try {
+        await relay(9000, (await chainNow()) - 7200, 2);
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("StaleOracleUpdate");
+      }
+    });
+
+    it("letting a low score go stale does not restore the neutral fallback", async () => {
+      await program.methods
+        .updateOracle(true, new anchor.BN(2))
+        .accounts({ oracle: oraclePda(oracleKey.publicKey), admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+      await relay(0, await chainNow(), 2);
+      await new Promise((r) => setTimeout(r, 4000));
+
+      const score = await trust();
+      expect(score.oracleFallback).to.be.true;
+      expect(score.reputation).to.be.within(2490, 2500);
+    });
+
+  });
+
+  // ==================== WORK VALIDATION ====================
+
+  describe("validate_work", () => {
+    it("validates agent work with task hash", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const taskHash = crypto.createHash("sha256").update("task-001").digest();
+      const [valPda] = validationPda(identityPda, taskHash);
+
+      await program.methods
+        .validateWork([...taskHash] as any, true, "https://example.com/evidence")
+        .accounts({
+          agentIdentity: identityPda,
+          validator: randomWallet.publicKey,
+        })
+        .signers([randomWallet])
+        .rpc();
+
+      const record = await program.account.validationRecord.fetch(valPda);
+      expect(record.agentId.toBase58()).to.equal(identityPda.toBase58());
+      expect(record.validator.toBase58()).to.equal(randomWallet.publicKey.toBase58());
+      expect(record.passed).to.be.true;
+      expect(record.evidenceUri).to.equal("https://example.com/evidence");
+    });
+
+    it("records the agent authority's co-signature", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const uncosigned = validationPda(identityPda, crypto.createHash("sha256").update("task-001").digest())[0];
+      const taskHash = crypto.createHash("sha256").update("task-cosigned").digest();
+      const [valPda] = validationPda(identityPda, taskHash);
+
+      // without the co-signature the record cannot back feedback
+      expect((await program.account.validationRecord.fetch(uncosigned)).agentCosigned).to.be.false;
+      try {
+        await program.methods
+          .submitFeedback(true, "not co-signed", { validation: {} })
+          .accounts({
+            agentIdentity: identityPda,
+            reviewer: randomWallet.publicKey,
+            interactionEvidence: uncosigned,
+          })
+          .signers([randomWallet])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("InvalidInteractionProof");
+      }
+
+      // the agent's authority (walletC since transfer_authority) co-signs
+      await program.methods
+        .validateWork([...taskHash] as any, true, "https://example.com/evidence")
+        .accounts({
+          agentIdentity: identityPda,
+          validator: randomWallet.publicKey,
+          agentAuthority: walletC.publicKey,
+        })
+        .signers([randomWallet, walletC])
+        .rpc();
+
+      expect((await program.account.validationRecord.fetch(valPda)).agentCosigned).to.be.true;
+    });
+
+    it("only the agent authority can co-sign", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const taskHash = crypto.createHash("sha256").update("task-forged").digest();
+
+      try {
+        await program.methods
+          .validateWork([...taskHash] as any, true, "https://example.com/forged")
+          .accounts({
+            agentIdentity: identityPda,
+            validator: walletD.publicKey,
+            agentAuthority: walletD.publicKey,
+          })
+          .signers([walletD])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("Unauthorized");
+      }
+    });
+
+    it("cannot validate same task twice", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const taskHash = crypto.createHash("sha256").update("task-001").digest();
+
+      try {
+        await program.methods
+          .validateWork([...taskHash] as any, false, "https://example.com/evidence2")
+          .accounts({
+            agentIdentity: identityPda,
+            validator: walletD.publicKey,
+          })
+          .signers([walletD])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("already in use");
+      }
+    });
+
+    it("flags feedback backed by the reviewer's validation as a verified interaction", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const taskHash = crypto.createHash("sha256").update("task-cosigned").digest();
+      const [valPda] = validationPda(identityPda, taskHash);
+      const [recordPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("feedback"), identityPda.toBuffer(), randomWallet.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .submitFeedback(true, "delivered as validated", { validation: {} })
+        .accounts({
+          agentIdentity: identityPda,
+          reviewer: randomWallet.publicKey,
+          interactionEvidence: valPda,
+        })
+        .signers([randomWallet])
+        .rpc();
+
+      const record = await program.account.feedbackRecord.fetch(recordPda);
+      expect(record.positive).to.be.true;
+      expect(record.verifiedInteraction).to.be.true;
+    });
+
+    it("a validation the reviewer made alone does not prove an interaction", async () => {
+      const [identityPda] = agentPda(owner.publicKey);
+      const taskHash = crypto.createHash("sha256").update("task-002").digest();
+      const [valPda] = validationPda(identityPda, taskHash);
+
+      await program.methods
+        .validateWork([...taskHash] as any, true, "https://example.com/self-made")
+        .accounts({
+          agentIdentity: identityPda,
+          validator: walletD.publicKey,
+        })
+        .signers([walletD])
+        .rpc();
+      expect((await program.account.validationRecord.fetch(valPda)).agentCosigned).to.be.false;
+
+      try {
+        await program.methods
+          .submitFeedback(true, "trust me", { validation: {} })
+          .accounts({
+            agentIdentity: identityPda,
+            reviewer: walletD.publicKey,
+            interactionEvidence: valPda,
+          })
+          .signers([walletD])
+          .rpc();
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.toString()).to.contain("InvalidInteractionProof");
       }
     });
   });